@@ -0,0 +1,166 @@
+//! Role-based access control shared by `l1x-ft` and `l1x-nft`.
+//!
+//! A contract keeps a [`Roles`] value in its state, grants [`Role::Admin`] to
+//! the deployer in `new` and replaces `caller_address() ==
+//! contract_owner_address()` checks with [`Roles::assert_role`]. Admins can
+//! grant and revoke every role, any member can renounce its own role.
+//!
+//! Changes of the members are returned as a [`RoleChange`], which the
+//! contract emits as a variant of its own event type, so every event of a
+//! contract decodes as that one type.
+use std::collections::BTreeSet;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk::store::LookupMap;
+use l1x_sdk::types::Address;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub enum Role {
+    /// Can grant and revoke every role.
+    Admin,
+    /// Can mint new tokens.
+    Minter,
+    /// Can burn tokens it doesn't own.
+    Burner,
+    /// Can pause and unpause transfers.
+    Pauser,
+}
+
+/// A change of the members of a role.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoleChange {
+    /// `account` is granted `role` by `sender`.
+    Granted { role: Role, account: Address, sender: Address },
+    /// `role` is revoked from `account` by `sender`. `sender` is `account`
+    /// itself when the role is renounced.
+    Revoked { role: Role, account: Address, sender: Address },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Roles {
+    members: LookupMap<Role, BTreeSet<Address>>,
+}
+
+impl Roles {
+    pub fn new(prefix: Vec<u8>) -> Self {
+        Self { members: LookupMap::new(prefix) }
+    }
+
+    pub fn has_role(&self, role: Role, account: &Address) -> bool {
+        self.members
+            .get(&role)
+            .map(|members| members.contains(account))
+            .unwrap_or(false)
+    }
+
+    pub fn assert_role(&self, role: Role, account: &Address) {
+        assert!(
+            self.has_role(role, account),
+            "{} is missing role {:?}",
+            account,
+            role
+        );
+    }
+
+    /// Grants `role` to `account` on behalf of `sender`, who must be an admin.
+    /// Returns `None` if `account` already has the role.
+    #[must_use]
+    pub fn grant_role(
+        &mut self,
+        sender: &Address,
+        role: Role,
+        account: Address,
+    ) -> Option<RoleChange> {
+        self.assert_role(Role::Admin, sender);
+        self.internal_grant_role(sender, role, account)
+    }
+
+    /// Revokes `role` from `account` on behalf of `sender`, who must be an
+    /// admin. Returns `None` if `account` doesn't have the role.
+    #[must_use]
+    pub fn revoke_role(
+        &mut self,
+        sender: &Address,
+        role: Role,
+        account: Address,
+    ) -> Option<RoleChange> {
+        self.assert_role(Role::Admin, sender);
+        self.internal_revoke_role(sender, role, account)
+    }
+
+    /// Drops `role` from `sender`.
+    #[must_use]
+    pub fn renounce_role(
+        &mut self,
+        sender: &Address,
+        role: Role,
+    ) -> Option<RoleChange> {
+        self.assert_role(role, sender);
+        self.internal_revoke_role(sender, role, *sender)
+    }
+
+    /// Grants `role` without checking the permissions of `sender`. Used to
+    /// bootstrap the deployer in the contract constructor.
+    #[must_use]
+    pub fn internal_grant_role(
+        &mut self,
+        sender: &Address,
+        role: Role,
+        account: Address,
+    ) -> Option<RoleChange> {
+        let inserted = match self.members.get_mut(&role) {
+            Some(members) => members.insert(account),
+            None => {
+                self.members.insert(role, BTreeSet::from([account]));
+                true
+            }
+        };
+
+        if !inserted {
+            return None;
+        }
+
+        l1x_sdk::msg(&format!(
+            "Role {:?} granted to {} by {}",
+            role, account, sender
+        ));
+
+        Some(RoleChange::Granted { role, account, sender: *sender })
+    }
+
+    fn internal_revoke_role(
+        &mut self,
+        sender: &Address,
+        role: Role,
+        account: Address,
+    ) -> Option<RoleChange> {
+        let removed = match self.members.get_mut(&role) {
+            Some(members) => members.remove(&account),
+            None => false,
+        };
+
+        if !removed {
+            return None;
+        }
+
+        l1x_sdk::msg(&format!(
+            "Role {:?} revoked from {} by {}",
+            role, account, sender
+        ));
+
+        Some(RoleChange::Revoked { role, account, sender: *sender })
+    }
+}
@@ -0,0 +1,91 @@
+use l1x_access_control::{Role, RoleChange, Roles};
+use l1x_mock_host as host;
+
+fn bootstrap() -> Roles {
+    let mut roles = Roles::new(b"roles".to_vec());
+    let _ = roles.internal_grant_role(
+        &host::address(0),
+        Role::Admin,
+        host::address(1),
+    );
+    roles
+}
+
+#[test]
+fn admin_grants_and_revokes_roles() {
+    let mut roles = bootstrap();
+    let (admin, minter) = (host::address(1), host::address(2));
+
+    assert_eq!(
+        roles.grant_role(&admin, Role::Minter, minter),
+        Some(RoleChange::Granted {
+            role: Role::Minter,
+            account: minter,
+            sender: admin
+        })
+    );
+    assert!(roles.has_role(Role::Minter, &minter));
+    assert!(!roles.has_role(Role::Burner, &minter));
+
+    assert_eq!(
+        roles.revoke_role(&admin, Role::Minter, minter),
+        Some(RoleChange::Revoked {
+            role: Role::Minter,
+            account: minter,
+            sender: admin
+        })
+    );
+    assert!(!roles.has_role(Role::Minter, &minter));
+}
+
+#[test]
+fn unchanged_members_report_nothing() {
+    let mut roles = bootstrap();
+    let admin = host::address(1);
+
+    assert!(roles.grant_role(&admin, Role::Pauser, host::address(3)).is_some());
+    assert_eq!(roles.grant_role(&admin, Role::Pauser, host::address(3)), None);
+    assert_eq!(roles.revoke_role(&admin, Role::Minter, host::address(3)), None);
+}
+
+#[test]
+fn role_changes_are_left_to_the_contract_to_emit() {
+    let mut roles = bootstrap();
+
+    let _ = roles.grant_role(&host::address(1), Role::Minter, host::address(2));
+
+    assert!(host::events().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_grant() {
+    let mut roles = bootstrap();
+
+    let _ = roles.grant_role(&host::address(2), Role::Minter, host::address(2));
+}
+
+#[test]
+fn members_can_renounce() {
+    let mut roles = bootstrap();
+    let admin = host::address(1);
+
+    assert_eq!(
+        roles.renounce_role(&admin, Role::Admin),
+        Some(RoleChange::Revoked {
+            role: Role::Admin,
+            account: admin,
+            sender: admin
+        })
+    );
+
+    assert!(!roles.has_role(Role::Admin, &admin));
+}
+
+#[test]
+#[should_panic(expected = "is missing role Burner")]
+fn renounce_requires_membership() {
+    let mut roles = bootstrap();
+
+    let _ = roles.renounce_role(&host::address(1), Role::Burner);
+}
@@ -0,0 +1,12 @@
+//! Basis point arithmetic shared by the `l1x-ft` fees and the `l1x-nft`
+//! royalties.
+
+/// Denominator of all basis point values.
+pub const MAX_BPS: u16 = 10_000;
+
+/// Returns `amount * bps / MAX_BPS` rounded down, without overflowing.
+pub fn bps_of(amount: u128, bps: u16) -> u128 {
+    let bps = u128::from(bps);
+    let max_bps = u128::from(MAX_BPS);
+    amount / max_bps * bps + amount % max_bps * bps / max_bps
+}
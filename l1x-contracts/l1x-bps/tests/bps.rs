@@ -0,0 +1,20 @@
+use l1x_bps::{bps_of, MAX_BPS};
+
+#[test]
+fn bps_of_rounds_down() {
+    assert_eq!(bps_of(1_000, 250), 25);
+    assert_eq!(bps_of(999, 1), 0);
+    assert_eq!(bps_of(19_999, 5_000), 9_999);
+}
+
+#[test]
+fn max_bps_is_the_whole_amount() {
+    assert_eq!(bps_of(12_345, MAX_BPS), 12_345);
+    assert_eq!(bps_of(12_345, 0), 0);
+}
+
+#[test]
+fn bps_of_does_not_overflow() {
+    assert_eq!(bps_of(u128::MAX, MAX_BPS), u128::MAX);
+    assert_eq!(bps_of(u128::MAX, 5_000), u128::MAX / 2);
+}
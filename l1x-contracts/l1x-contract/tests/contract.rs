@@ -0,0 +1,33 @@
+use l1x_contract::Contract;
+use l1x_mock_host as host;
+
+#[test]
+fn add_name_appends_to_the_list() {
+    Contract::new();
+    Contract::add_name("Alice".to_string());
+    Contract::add_name("Bob".to_string());
+
+    assert_eq!(Contract::get_names(), vec!["Alice", "Bob"]);
+}
+
+#[test]
+fn hello_greets_every_name() {
+    Contract::add_name("Alice".to_string());
+    Contract::add_name("Bob".to_string());
+
+    Contract::hello();
+
+    assert_eq!(host::logs(), vec!["Hello, Alice!", "Hello, Bob!"]);
+}
+
+#[test]
+fn info_reports_block_data() {
+    host::set_block(42, 1_700_000_000);
+
+    Contract::info();
+
+    let logs = host::logs();
+    assert_eq!(logs.len(), 1);
+    assert!(logs[0].contains("block_number=42"));
+    assert!(logs[0].contains("block_timestamp=1700000000"));
+}
@@ -0,0 +1,54 @@
+use l1x_cross_contract::Contract;
+use l1x_mock_host as host;
+use l1x_sdk::types::Address;
+
+fn l1x_contract() -> Address {
+    Address::try_from(b"l1x_contract\0\0\0\0\0\0\0\0".to_vec()).unwrap()
+}
+
+#[test]
+fn hello_reads_names_from_the_callee() {
+    host::mock_call(
+        &l1x_contract(),
+        "get_names",
+        Some(serde_json::to_vec(&vec!["Alice"]).unwrap()),
+    );
+
+    Contract::hello();
+
+    let calls = host::calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].method_name, "get_names");
+    assert!(calls[0].read_only);
+    assert!(host::logs()[0].contains("Alice"));
+}
+
+#[test]
+fn hello_logs_none_without_a_response() {
+    Contract::hello();
+
+    assert_eq!(host::logs(), vec!["None"]);
+}
+
+#[test]
+fn add_name_forwards_the_argument() {
+    host::mock_call(&l1x_contract(), "add_name", Some(vec![]));
+
+    Contract::add_name("Alice".to_string());
+
+    let calls = host::calls();
+    assert_eq!(calls.len(), 1);
+    assert!(!calls[0].read_only);
+    assert_eq!(calls[0].args, br#"{"name":"Alice"}"#.to_vec());
+    assert_eq!(
+        host::logs(),
+        vec!["The external contract returned the empty result"]
+    );
+}
+
+#[test]
+fn emit_event_publishes_one_event() {
+    Contract::emit_event();
+
+    assert_eq!(host::events().len(), 1);
+}
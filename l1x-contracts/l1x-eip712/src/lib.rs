@@ -0,0 +1,58 @@
+//! EIP-712 typed-data signatures shared by `l1x-ft` and `l1x-nft`.
+//!
+//! A contract hashes its message type into a struct hash and
+//! [`typed_data_digest`] binds it to the domain: the contract name, version
+//! "1", [`L1X_CHAIN_ID`] and the contract instance address. The signer signs
+//! the digest off-chain with `eth_signTypedData_v4` and the contract checks
+//! it with [`recover_signer`].
+use std::str::FromStr;
+
+use ethers::abi::{encode, Token};
+use ethers::types::{Signature, H256, U256};
+use ethers::utils::keccak256;
+use l1x_sdk::types::Address;
+
+/// Chain id of the L1X network used in the EIP-712 domain.
+pub const L1X_CHAIN_ID: u64 = 1776;
+
+const DOMAIN_VERSION: &str = "1";
+
+const DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+/// Returns the digest of the message with `struct_hash` signed for the
+/// contract `name` at `verifying_contract`.
+pub fn typed_data_digest(
+    name: &str,
+    verifying_contract: &Address,
+    struct_hash: [u8; 32],
+) -> [u8; 32] {
+    let domain_separator = keccak256(encode(&[
+        Token::FixedBytes(keccak256(DOMAIN_TYPE).to_vec()),
+        Token::FixedBytes(keccak256(name).to_vec()),
+        Token::FixedBytes(keccak256(DOMAIN_VERSION).to_vec()),
+        Token::Uint(U256::from(L1X_CHAIN_ID)),
+        Token::Address(to_evm_address(verifying_contract)),
+    ]));
+
+    keccak256([&[0x19, 0x01], &domain_separator[..], &struct_hash[..]].concat())
+}
+
+/// Recovers the address that signed `digest`.
+///
+/// - `signature`: 65-byte `r || s || v` signature, hex encoded
+pub fn recover_signer(digest: [u8; 32], signature: &str) -> Address {
+    let signature = match Signature::from_str(signature) {
+        Ok(signature) => signature,
+        Err(error) => panic!("{:?}", error.to_string()),
+    };
+
+    match signature.recover(H256::from(digest)) {
+        Ok(signer) => Address::from(signer.0),
+        Err(error) => panic!("{:?}", error.to_string()),
+    }
+}
+
+pub fn to_evm_address(address: &Address) -> ethers::types::Address {
+    ethers::types::Address::from_slice(address.as_bytes())
+}
@@ -0,0 +1,70 @@
+use ethers::abi::{encode, Token};
+use ethers::signers::LocalWallet;
+use ethers::types::{H256, U256};
+use ethers::utils::keccak256;
+use l1x_eip712::{
+    recover_signer, to_evm_address, typed_data_digest, L1X_CHAIN_ID,
+};
+use l1x_mock_host as host;
+use l1x_sdk::types::Address;
+
+fn wallet() -> (LocalWallet, Address) {
+    let wallet = LocalWallet::from_bytes(&[0x33; 32]).unwrap();
+    let address = Address::from(wallet.address().0);
+    (wallet, address)
+}
+
+#[test]
+fn to_evm_address_keeps_the_bytes() {
+    let address = host::address(7);
+
+    assert_eq!(to_evm_address(&address).as_bytes(), address.as_bytes());
+}
+
+#[test]
+fn digest_binds_the_domain() {
+    let struct_hash = keccak256("message");
+    let domain_separator = keccak256(encode(&[
+        Token::FixedBytes(
+            keccak256(
+                "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+            )
+            .to_vec(),
+        ),
+        Token::FixedBytes(keccak256("Name").to_vec()),
+        Token::FixedBytes(keccak256("1").to_vec()),
+        Token::Uint(U256::from(L1X_CHAIN_ID)),
+        Token::Address(to_evm_address(&host::address(1))),
+    ]));
+    let expected = keccak256(
+        [&[0x19, 0x01], &domain_separator[..], &struct_hash[..]].concat(),
+    );
+
+    let digest = typed_data_digest("Name", &host::address(1), struct_hash);
+    assert_eq!(digest, expected);
+    assert_ne!(
+        typed_data_digest("Other", &host::address(1), struct_hash),
+        digest
+    );
+    assert_ne!(
+        typed_data_digest("Name", &host::address(2), struct_hash),
+        digest
+    );
+}
+
+#[test]
+fn recover_signer_returns_the_signer() {
+    let (wallet, signer) = wallet();
+    let digest = typed_data_digest("Name", &host::address(1), [0x44; 32]);
+
+    let signature = wallet.sign_hash(H256::from(digest)).unwrap().to_string();
+
+    assert_eq!(recover_signer(digest, &signature), signer);
+    assert_ne!(recover_signer([0x55; 32], &signature), signer);
+}
+
+#[test]
+#[should_panic]
+fn recover_signer_rejects_malformed_signatures() {
+    recover_signer([0x44; 32], "not a signature");
+}
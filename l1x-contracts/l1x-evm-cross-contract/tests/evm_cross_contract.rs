@@ -0,0 +1,60 @@
+use l1x_evm_cross_contract::Contract;
+use l1x_mock_host as host;
+use l1x_sdk::types::{Address, U128};
+
+fn erc20() -> Address {
+    host::address(0xe2)
+}
+
+fn returns<R>(signature: [u8; 4], value: &R) -> Vec<u8>
+where
+    R: solabi::encode::Encode + solabi::decode::Decode,
+{
+    let func: solabi::FunctionEncoder<(), R> =
+        solabi::FunctionEncoder::new(signature);
+    func.encode_returns(value)
+}
+
+#[test]
+fn balance_of_decodes_the_evm_result() {
+    Contract::new(erc20());
+    host::mock_call(
+        &erc20(),
+        "",
+        Some(returns(
+            solabi::selector!("balanceOf(address)"),
+            &(solabi::U256::from(500u128),),
+        )),
+    );
+
+    assert_eq!(Contract::balance_of(host::address(1)), "500");
+
+    let calls = host::calls();
+    assert_eq!(calls.len(), 1);
+    assert!(calls[0].read_only);
+    assert_eq!(&calls[0].args[..4], &solabi::selector!("balanceOf(address)"));
+}
+
+#[test]
+fn transfer_sends_a_mutating_call() {
+    Contract::new(erc20());
+    host::mock_call(
+        &erc20(),
+        "",
+        Some(returns(solabi::selector!("transfer(address,uint256)"), &(true,))),
+    );
+
+    Contract::transfer(host::address(1), U128(10));
+
+    let calls = host::calls();
+    assert_eq!(calls.len(), 1);
+    assert!(!calls[0].read_only);
+}
+
+#[test]
+#[should_panic(expected = "Function returned nothing")]
+fn balance_of_requires_a_response() {
+    Contract::new(erc20());
+
+    Contract::balance_of(host::address(1));
+}
@@ -0,0 +1,96 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk::store::LookupMap;
+use l1x_sdk::types::{Address, U128};
+use l1x_sdk::{caller_address, contract, contract_owner_address};
+
+const STORAGE_CONTRACT_KEY: &[u8] = b"state";
+const STORAGE_DEPOSITS_KEY: &[u8] = b"deposits";
+
+/// Message asking the receiver to send the tokens back.
+const REFUND_MSG: &str = "refund";
+
+/// Example receiver for `L1xFtErc20::ft_transfer_call`. It credits the
+/// received tokens to the sender's deposit, unless the message asks for a
+/// refund.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FtReceiver {
+    token_address: Address,
+    deposits: LookupMap<Address, u128>,
+}
+
+#[contract]
+impl FtReceiver {
+    /// - `token_address`: instance address of the accepted l1x-ft contract
+    pub fn new(token_address: Address) {
+        assert_eq!(
+            caller_address(),
+            contract_owner_address(),
+            "Only the contract owner can call this method"
+        );
+
+        let mut contract = Self {
+            token_address,
+            deposits: LookupMap::new(STORAGE_DEPOSITS_KEY.to_vec()),
+        };
+        contract.save();
+    }
+
+    /// Called by the token contract after `amount` has been transferred.
+    /// Returns the amount that the token contract should refund to
+    /// `sender_id`.
+    pub fn ft_on_transfer(
+        sender_id: Address,
+        amount: U128,
+        msg: String,
+    ) -> U128 {
+        let mut contract = Self::load();
+
+        assert_eq!(
+            caller_address(),
+            contract.token_address,
+            "Only the accepted token can call this method"
+        );
+
+        if msg == REFUND_MSG {
+            l1x_sdk::msg(&format!(
+                "Refunding {} tokens to {}",
+                amount.0, sender_id
+            ));
+            return amount;
+        }
+
+        let deposit = contract.deposit_of(&sender_id);
+        contract.deposits.insert(sender_id.clone(), deposit + amount.0);
+        l1x_sdk::msg(&format!(
+            "Deposited {} tokens for {}",
+            amount.0, sender_id
+        ));
+
+        contract.save();
+
+        0.into()
+    }
+
+    pub fn get_deposit(account_id: Address) -> U128 {
+        let contract = Self::load();
+        contract.deposit_of(&account_id).into()
+    }
+
+    fn deposit_of(&self, account_id: &Address) -> u128 {
+        self.deposits.get(account_id).copied().unwrap_or_default()
+    }
+
+    fn load() -> Self {
+        match l1x_sdk::storage_read(STORAGE_CONTRACT_KEY) {
+            Some(bytes) => Self::try_from_slice(&bytes).unwrap(),
+            None => panic!("The contract isn't initialized"),
+        }
+    }
+
+    fn save(&mut self) {
+        l1x_sdk::storage_write(
+            STORAGE_CONTRACT_KEY,
+            &self.try_to_vec().unwrap(),
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use l1x_ft_receiver::FtReceiver;
+use l1x_mock_host as host;
+use l1x_sdk::types::U128;
+
+fn setup() {
+    FtReceiver::new(host::address(0xf7));
+    host::set_caller(host::address(0xf7));
+}
+
+#[test]
+#[should_panic(expected = "Only the contract owner can call this method")]
+fn new_requires_the_owner() {
+    host::set_caller(host::address(1));
+
+    FtReceiver::new(host::address(0xf7));
+}
+
+#[test]
+fn on_transfer_keeps_the_deposit() {
+    setup();
+
+    let unused =
+        FtReceiver::ft_on_transfer(host::address(1), U128(40), String::new());
+
+    assert_eq!(unused.0, 0);
+    assert_eq!(FtReceiver::get_deposit(host::address(1)).0, 40);
+}
+
+#[test]
+fn on_transfer_refunds_on_request() {
+    setup();
+
+    let unused = FtReceiver::ft_on_transfer(
+        host::address(1),
+        U128(40),
+        "refund".to_string(),
+    );
+
+    assert_eq!(unused.0, 40);
+    assert_eq!(FtReceiver::get_deposit(host::address(1)).0, 0);
+}
+
+#[test]
+#[should_panic(expected = "Only the accepted token can call this method")]
+fn on_transfer_rejects_other_callers() {
+    setup();
+    host::set_caller(host::address(1));
+
+    FtReceiver::ft_on_transfer(host::address(1), U128(40), String::new());
+}
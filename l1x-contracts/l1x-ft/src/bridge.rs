@@ -0,0 +1,229 @@
+//! Cross-chain bridge to EVM chains over x-talk.
+//!
+//! Outbound: `ft_bridge_out` burns (or locks in the contract instance) the
+//! tokens and records a [`BridgeOutPayload`] under a new global tx id. The
+//! x-talk validators sign [`BridgeOutPayload::hash_to_sign`] and submit it to
+//! the remote bridge contract of the destination network, which releases the
+//! tokens there.
+//!
+//! Inbound: the remote bridge contract emits `BridgeLocked`, x-talk delivers
+//! the log through `save_event_data` and the tokens are minted (or unlocked)
+//! for the recipient. Every global tx id is processed only once.
+//!
+//! The instance also holds the vesting escrow, so locked tokens are counted
+//! in `Bridge::locked` and unlocking never releases more than was locked.
+use std::collections::BTreeMap;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use ethers::abi::{encode, Token};
+use ethers::prelude::{parse_log, EthEvent};
+use ethers::utils::keccak256;
+use l1x_eip712::{to_evm_address, L1X_CHAIN_ID};
+use l1x_sdk::store::LookupMap;
+use l1x_sdk::types::{Address, U128};
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Default,
+)]
+pub enum BridgeMode {
+    /// Outbound tokens are burned, inbound tokens are minted.
+    #[default]
+    BurnMint,
+    /// Outbound tokens are locked in the contract instance, inbound tokens
+    /// are unlocked from it.
+    LockUnlock,
+}
+
+/// Outbound transfer waiting to be executed on the destination network.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+pub struct BridgeOutPayload {
+    pub global_tx_id: [u8; 32],
+    pub sender: Address,
+    pub dest_network: String,
+    pub dest_address: Address,
+    /// Remote bridge contract the payload is executed by
+    pub dest_contract: Address,
+    pub amount: U128,
+}
+
+impl BridgeOutPayload {
+    /// Returns the digest the validators sign:
+    /// `keccak256(abi.encode(globalTxId, token, sourceChainId, destNetwork,
+    /// destContract, destAddress, amount))`.
+    pub fn hash_to_sign(&self, token: &Address) -> [u8; 32] {
+        keccak256(encode(&[
+            Token::FixedBytes(self.global_tx_id.to_vec()),
+            Token::Address(to_evm_address(token)),
+            Token::Uint(L1X_CHAIN_ID.into()),
+            Token::String(self.dest_network.clone()),
+            Token::Address(to_evm_address(&self.dest_contract)),
+            Token::Address(to_evm_address(&self.dest_address)),
+            Token::Uint(self.amount.0.into()),
+        ]))
+    }
+}
+
+/// `BridgeLocked` event of the remote bridge contract.
+#[derive(Clone, Debug, EthEvent)]
+#[ethevent(name = "BridgeLocked")]
+struct BridgeLockedSolidityEvent {
+    #[ethevent(indexed)]
+    global_tx_id: [u8; 32],
+    #[ethevent(indexed)]
+    sender: ethers::types::Address,
+    #[ethevent(indexed)]
+    recipient: ethers::types::Address,
+    amount: ethers::types::U256,
+}
+
+/// Inbound transfer parsed from a `BridgeLocked` log.
+pub(crate) struct BridgeIn {
+    pub global_tx_id: [u8; 32],
+    pub source_contract: Address,
+    pub recipient: Address,
+    pub amount: u128,
+}
+
+impl BridgeIn {
+    /// Parses the JSON encoded ethers `Log` delivered by x-talk.
+    pub fn from_log(event_data: &[u8]) -> Self {
+        let log: ethers::types::Log = match serde_json::from_slice(event_data) {
+            Ok(log) => log,
+            Err(error) => panic!("{}", error.to_string()),
+        };
+        let source_contract = Address::from(log.address.0);
+        let event = match parse_log::<BridgeLockedSolidityEvent>(log) {
+            Ok(event) => event,
+            Err(error) => panic!("{}", error.to_string()),
+        };
+
+        Self {
+            global_tx_id: event.global_tx_id,
+            source_contract,
+            recipient: Address::from(event.recipient.0),
+            amount: u128::try_from(event.amount)
+                .expect("The bridged amount is overflowed"),
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct Bridge {
+    pub mode: BridgeMode,
+    /// Remote bridge contract of every supported network
+    pub remotes: BTreeMap<String, Address>,
+    /// Tokens held by the contract instance for `BridgeMode::LockUnlock`
+    pub locked: u128,
+    nonce: u64,
+    payloads: LookupMap<[u8; 32], BridgeOutPayload>,
+    processed: LookupMap<[u8; 32], bool>,
+}
+
+impl Bridge {
+    pub fn new(payloads_prefix: Vec<u8>, processed_prefix: Vec<u8>) -> Self {
+        Self {
+            mode: BridgeMode::default(),
+            remotes: BTreeMap::new(),
+            locked: 0,
+            nonce: 0,
+            payloads: LookupMap::new(payloads_prefix),
+            processed: LookupMap::new(processed_prefix),
+        }
+    }
+
+    pub fn remote(&self, network: &str) -> Address {
+        *self
+            .remotes
+            .get(network)
+            .unwrap_or_else(|| panic!("Unsupported network {}", network))
+    }
+
+    /// Records an outbound transfer under a new global tx id, derived from
+    /// the token address and a counter.
+    pub fn record_out(
+        &mut self,
+        token: &Address,
+        sender: Address,
+        dest_network: String,
+        dest_address: Address,
+        amount: u128,
+    ) -> BridgeOutPayload {
+        let global_tx_id = keccak256(encode(&[
+            Token::Address(to_evm_address(token)),
+            Token::Uint(self.nonce.into()),
+        ]));
+        self.nonce += 1;
+
+        let payload = BridgeOutPayload {
+            global_tx_id,
+            sender,
+            dest_contract: self.remote(&dest_network),
+            dest_network,
+            dest_address,
+            amount: amount.into(),
+        };
+        self.payloads.insert(global_tx_id, payload.clone());
+
+        payload
+    }
+
+    pub fn payload(&self, global_tx_id: &[u8; 32]) -> &BridgeOutPayload {
+        self.payloads.get(global_tx_id).unwrap_or_else(|| {
+            panic!(
+                "invalid global transaction id: {}",
+                hex::encode(global_tx_id)
+            )
+        })
+    }
+
+    /// Checks that `bridge_in` comes from a known remote bridge contract and
+    /// marks its global tx id as processed.
+    pub fn accept_in(&mut self, bridge_in: &BridgeIn) {
+        assert!(
+            self.remotes
+                .values()
+                .any(|remote| *remote == bridge_in.source_contract),
+            "Unknown bridge contract {}",
+            bridge_in.source_contract
+        );
+        assert!(
+            !self.is_processed(&bridge_in.global_tx_id),
+            "Global transaction id {} is already processed",
+            hex::encode(bridge_in.global_tx_id)
+        );
+
+        self.processed.insert(bridge_in.global_tx_id, true);
+    }
+
+    pub fn is_processed(&self, global_tx_id: &[u8; 32]) -> bool {
+        self.processed.get(global_tx_id).copied().unwrap_or_default()
+    }
+}
+
+/// Parses a global tx id from its hex representation, with or without `0x`.
+pub(crate) fn parse_global_tx_id(global_tx_id: &str) -> [u8; 32] {
+    let bytes = match hex::decode(global_tx_id.trim_start_matches("0x")) {
+        Ok(bytes) => bytes,
+        Err(error) => panic!("{}", error.to_string()),
+    };
+    bytes.try_into().unwrap_or_else(|_| {
+        panic!("invalid global transaction id: {}", global_tx_id)
+    })
+}
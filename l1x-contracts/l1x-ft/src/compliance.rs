@@ -0,0 +1,155 @@
+//! Blocklist and allowlist transfer restrictions.
+//!
+//! In [`ComplianceMode::Blocklist`] mode blocklisted accounts can't send,
+//! receive, be minted to or approve tokens. In [`ComplianceMode::Allowlist`]
+//! mode only allowlisted accounts can. Both lists are kept when the mode
+//! changes, so an admin can prepare a list before switching to it.
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk::store::LookupMap;
+use l1x_sdk::types::Address;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Default,
+)]
+pub enum ComplianceMode {
+    /// Anyone can hold and transfer tokens.
+    #[default]
+    Open,
+    /// Blocklisted accounts are frozen.
+    Blocklist,
+    /// Only allowlisted accounts can hold and transfer tokens.
+    Allowlist,
+}
+
+/// Result of `ft_can_transfer`. The Borsh encoding of a variant is its
+/// one-byte reason code, `Ok` being 0.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+)]
+pub enum TransferRestriction {
+    Ok,
+    Paused,
+    SenderBlocklisted,
+    RecipientBlocklisted,
+    SenderNotAllowlisted,
+    RecipientNotAllowlisted,
+    InsufficientBalance,
+    /// The recipient is the contract instance, which holds the vesting and
+    /// bridge escrow.
+    RecipientIsContract,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct Compliance {
+    mode: ComplianceMode,
+    blocklist: LookupMap<Address, bool>,
+    allowlist: LookupMap<Address, bool>,
+}
+
+impl Compliance {
+    pub fn new(blocklist_prefix: Vec<u8>, allowlist_prefix: Vec<u8>) -> Self {
+        Self {
+            mode: ComplianceMode::Open,
+            blocklist: LookupMap::new(blocklist_prefix),
+            allowlist: LookupMap::new(allowlist_prefix),
+        }
+    }
+
+    pub fn mode(&self) -> ComplianceMode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: ComplianceMode) {
+        self.mode = mode;
+    }
+
+    pub fn is_blocklisted(&self, account_id: &Address) -> bool {
+        self.blocklist.get(account_id).copied().unwrap_or_default()
+    }
+
+    pub fn is_allowlisted(&self, account_id: &Address) -> bool {
+        self.allowlist.get(account_id).copied().unwrap_or_default()
+    }
+
+    pub fn set_blocklisted(&mut self, account_id: Address, blocklisted: bool) {
+        Self::set_listed(&mut self.blocklist, account_id, blocklisted);
+    }
+
+    pub fn set_allowlisted(&mut self, account_id: Address, allowlisted: bool) {
+        Self::set_listed(&mut self.allowlist, account_id, allowlisted);
+    }
+
+    /// Returns the restriction on `sender_id` sending tokens to
+    /// `recipient_id` in the current mode.
+    pub fn transfer_restriction(
+        &self,
+        sender_id: &Address,
+        recipient_id: &Address,
+    ) -> TransferRestriction {
+        match self.mode {
+            ComplianceMode::Open => TransferRestriction::Ok,
+            ComplianceMode::Blocklist if self.is_blocklisted(sender_id) => {
+                TransferRestriction::SenderBlocklisted
+            }
+            ComplianceMode::Blocklist if self.is_blocklisted(recipient_id) => {
+                TransferRestriction::RecipientBlocklisted
+            }
+            ComplianceMode::Allowlist if !self.is_allowlisted(sender_id) => {
+                TransferRestriction::SenderNotAllowlisted
+            }
+            ComplianceMode::Allowlist if !self.is_allowlisted(recipient_id) => {
+                TransferRestriction::RecipientNotAllowlisted
+            }
+            _ => TransferRestriction::Ok,
+        }
+    }
+
+    /// Panics if `account_id` can't hold or move tokens in the current mode.
+    pub fn assert_permitted(&self, account_id: &Address) {
+        match self.mode {
+            ComplianceMode::Open => {}
+            ComplianceMode::Blocklist => {
+                assert!(
+                    !self.is_blocklisted(account_id),
+                    "{} is blocklisted",
+                    account_id
+                )
+            }
+            ComplianceMode::Allowlist => {
+                assert!(
+                    self.is_allowlisted(account_id),
+                    "{} is not allowlisted",
+                    account_id
+                )
+            }
+        }
+    }
+
+    fn set_listed(
+        list: &mut LookupMap<Address, bool>,
+        account_id: Address,
+        listed: bool,
+    ) {
+        if listed {
+            list.insert(account_id, true);
+        } else {
+            list.remove(account_id);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! EIP-712 signed messages: EIP-2612 permits and ERC20Votes delegations.
+//!
+//! The signer signs the standard `Permit` or `Delegation` type in the
+//! `l1x_eip712` domain and anyone can submit it with `ft_permit` or
+//! `ft_delegate_by_sig`. The per-signer nonce makes every signature
+//! single-use.
+use ethers::abi::{encode, Token};
+use ethers::types::U256;
+use ethers::utils::keccak256;
+use l1x_eip712::{to_evm_address, typed_data_digest};
+use l1x_sdk::types::Address;
+
+const PERMIT_TYPE: &str =
+    "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)";
+
+const DELEGATION_TYPE: &str =
+    "Delegation(address delegatee,uint256 nonce,uint256 expiry)";
+
+pub(crate) struct Permit<'a> {
+    pub owner_id: &'a Address,
+    pub spender_id: &'a Address,
+    pub amount: u128,
+    pub nonce: u64,
+    pub deadline: u64,
+}
+
+impl Permit<'_> {
+    /// Returns the EIP-712 digest signed by the owner.
+    pub fn digest(
+        &self,
+        token_name: &str,
+        verifying_contract: &Address,
+    ) -> [u8; 32] {
+        let struct_hash = keccak256(encode(&[
+            Token::FixedBytes(keccak256(PERMIT_TYPE).to_vec()),
+            Token::Address(to_evm_address(self.owner_id)),
+            Token::Address(to_evm_address(self.spender_id)),
+            Token::Uint(U256::from(self.amount)),
+            Token::Uint(U256::from(self.nonce)),
+            Token::Uint(U256::from(self.deadline)),
+        ]));
+
+        typed_data_digest(token_name, verifying_contract, struct_hash)
+    }
+}
+
+pub(crate) struct Delegation<'a> {
+    pub delegatee: &'a Address,
+    pub nonce: u64,
+    pub expiry: u64,
+}
+
+impl Delegation<'_> {
+    /// Returns the EIP-712 digest signed by the delegator.
+    pub fn digest(
+        &self,
+        token_name: &str,
+        verifying_contract: &Address,
+    ) -> [u8; 32] {
+        let struct_hash = keccak256(encode(&[
+            Token::FixedBytes(keccak256(DELEGATION_TYPE).to_vec()),
+            Token::Address(to_evm_address(self.delegatee)),
+            Token::Uint(U256::from(self.nonce)),
+            Token::Uint(U256::from(self.expiry)),
+        ]));
+
+        typed_data_digest(token_name, verifying_contract, struct_hash)
+    }
+}
@@ -0,0 +1,111 @@
+//! Events emitted by the token through `l1x_sdk::emit_event_experimental`.
+//!
+//! The payload of every event is the Borsh encoding of [`FtEvent`]. The same
+//! type derives serde, so indexers that link this crate can re-encode it as
+//! JSON, e.g.
+//!
+//! ```json
+//! {"Transfer": {"from": "0x…", "to": "0x…", "amount": "100"}}
+//! ```
+//!
+//! The schema is stable: variants and fields are never reordered or removed,
+//! new variants are only appended at the end of the enum.
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_access_control::{Role, RoleChange};
+use l1x_sdk::types::{Address, U128, U64};
+use serde::{Deserialize, Serialize};
+
+use crate::compliance::ComplianceMode;
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+pub enum FtEvent {
+    /// Emitted when `amount` tokens move from `from` to `to`.
+    Transfer { from: Address, to: Address, amount: U128 },
+    /// Emitted when `amount` new tokens are credited to `to`.
+    Mint { to: Address, amount: U128 },
+    /// Emitted when `amount` tokens held by `from` are destroyed.
+    Burn { from: Address, amount: U128 },
+    /// Emitted whenever the allowance of `spender` over the tokens of `owner`
+    /// changes, including when it is consumed by `ft_transfer_from`.
+    Approval { owner: Address, spender: Address, old: U128, new: U128 },
+    /// Emitted when `account` halts transfers, mints, burns and approvals.
+    Paused { account: Address },
+    /// Emitted when `account` resumes the operations halted by `Paused`.
+    Unpaused { account: Address },
+    /// Emitted when snapshot `id` of the balances is taken at `block_number`.
+    Snapshot { id: U64, block_number: U64 },
+    /// Emitted when `delegator` moves its voting power from `from_delegate` to
+    /// `to_delegate`.
+    DelegateChanged {
+        delegator: Address,
+        from_delegate: Option<Address>,
+        to_delegate: Address,
+    },
+    /// Emitted when the voting power of `delegate` changes.
+    DelegateVotesChanged {
+        delegate: Address,
+        previous_votes: U128,
+        new_votes: U128,
+    },
+    /// Emitted when a transfer of `gross` tokens from `from` to `to` is
+    /// charged a `fee`, of which `burned` is destroyed and the rest goes to
+    /// the treasury. `to` receives `net`. The individual movements are also
+    /// reported with `Transfer` and `Burn` events.
+    TransferFee {
+        from: Address,
+        to: Address,
+        gross: U128,
+        fee: U128,
+        burned: U128,
+        net: U128,
+    },
+    /// Emitted when `account` switches the transfer restrictions to `mode`.
+    ComplianceModeChanged { mode: ComplianceMode, account: Address },
+    /// Emitted when `amount` tokens are put in escrow to vest to
+    /// `beneficiary` under schedule `id`.
+    VestingCreated { id: U64, beneficiary: Address, amount: U128 },
+    /// Emitted when `amount` vested tokens of schedule `id` are transferred
+    /// to `beneficiary`.
+    VestingReleased { id: U64, beneficiary: Address, amount: U128 },
+    /// Emitted when schedule `id` is revoked and its `refunded` unvested
+    /// tokens go back to the funder.
+    VestingRevoked { id: U64, refunded: U128 },
+    /// Emitted when `sender` bridges `amount` tokens to `dest_address` on
+    /// `dest_network` under `global_tx_id`.
+    BridgeOut {
+        global_tx_id: [u8; 32],
+        sender: Address,
+        dest_network: String,
+        dest_address: Address,
+        amount: U128,
+    },
+    /// Emitted when `amount` tokens bridged under `global_tx_id` are credited
+    /// to `recipient`.
+    BridgeIn { global_tx_id: [u8; 32], recipient: Address, amount: U128 },
+    /// Emitted when `account` is granted `role` by `sender`.
+    RoleGranted { role: Role, account: Address, sender: Address },
+    /// Emitted when `role` is revoked from `account` by `sender`. `sender` is
+    /// `account` itself when the role is renounced.
+    RoleRevoked { role: Role, account: Address, sender: Address },
+}
+
+impl From<RoleChange> for FtEvent {
+    fn from(change: RoleChange) -> Self {
+        match change {
+            RoleChange::Granted { role, account, sender } => {
+                Self::RoleGranted { role, account, sender }
+            }
+            RoleChange::Revoked { role, account, sender } => {
+                Self::RoleRevoked { role, account, sender }
+            }
+        }
+    }
+}
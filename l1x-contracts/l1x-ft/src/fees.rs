@@ -0,0 +1,89 @@
+//! Fee-on-transfer configuration.
+//!
+//! A transfer of `gross` tokens charges `gross * rate_bps / 10_000` tokens.
+//! `burn_bps` of that fee is burned and the rest goes to the treasury, the
+//! recipient is credited with what remains. Transfers from or to an exempt
+//! account are free, as are mints and burns.
+use std::collections::BTreeSet;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_bps::{bps_of, MAX_BPS};
+use l1x_sdk::types::Address;
+use serde::Serialize;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Default, Clone)]
+pub struct FeeConfig {
+    rate_bps: u16,
+    burn_bps: u16,
+    treasury: Option<Address>,
+    exempt: BTreeSet<Address>,
+}
+
+/// The split of the fee charged on one transfer.
+pub(crate) struct TransferFee {
+    pub treasury: Option<Address>,
+    pub treasury_amount: u128,
+    pub burn_amount: u128,
+}
+
+impl TransferFee {
+    pub fn total(&self) -> u128 {
+        self.treasury_amount + self.burn_amount
+    }
+}
+
+impl FeeConfig {
+    pub fn set_rates(
+        &mut self,
+        rate_bps: u16,
+        burn_bps: u16,
+        treasury: Option<Address>,
+    ) {
+        assert!(rate_bps <= MAX_BPS, "rate_bps can't exceed {}", MAX_BPS);
+        assert!(burn_bps <= MAX_BPS, "burn_bps can't exceed {}", MAX_BPS);
+        assert!(
+            treasury.is_some() || burn_bps == MAX_BPS || rate_bps == 0,
+            "A treasury is required unless the whole fee is burned"
+        );
+
+        self.rate_bps = rate_bps;
+        self.burn_bps = burn_bps;
+        self.treasury = treasury;
+    }
+
+    pub fn set_exempt(&mut self, account_id: Address, exempt: bool) {
+        if exempt {
+            self.exempt.insert(account_id);
+        } else {
+            self.exempt.remove(&account_id);
+        }
+    }
+
+    /// Returns the fee charged when `sender_id` sends `amount` to
+    /// `recipient_id`.
+    pub fn fee_for(
+        &self,
+        sender_id: &Address,
+        recipient_id: &Address,
+        amount: u128,
+    ) -> TransferFee {
+        if self.rate_bps == 0
+            || self.exempt.contains(sender_id)
+            || self.exempt.contains(recipient_id)
+        {
+            return TransferFee {
+                treasury: None,
+                treasury_amount: 0,
+                burn_amount: 0,
+            };
+        }
+
+        let fee = bps_of(amount, self.rate_bps);
+        let burn_amount = bps_of(fee, self.burn_bps);
+        TransferFee {
+            treasury: self.treasury,
+            treasury_amount: fee - burn_amount,
+            burn_amount,
+        }
+    }
+}
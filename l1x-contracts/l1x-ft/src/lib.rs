@@ -1,14 +1,58 @@
 use std::collections::BTreeMap;
 
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use borsh::{BorshDeserialize, BorshSerialize};
-use l1x_sdk::store::LookupMap;
-use l1x_sdk::types::{Address, U128};
-use l1x_sdk::{caller_address, contract, contract_owner_address};
-use serde::Deserialize;
+use l1x_access_control::{RoleChange, Roles};
+use l1x_sdk::contract_interaction::ContractCall;
+use l1x_sdk::store::{LookupMap, Vector};
+use l1x_sdk::types::{Address, U128, U64};
+use l1x_sdk::{
+    caller_address, contract, contract_instance_address,
+    contract_owner_address, emit_event_experimental,
+};
+use serde::{Deserialize, Serialize};
+
+mod bridge;
+mod compliance;
+mod eip712;
+mod events;
+mod fees;
+mod snapshots;
+mod state;
+mod vesting;
+mod votes;
+
+pub use bridge::{BridgeMode, BridgeOutPayload};
+pub use compliance::{ComplianceMode, TransferRestriction};
+pub use events::FtEvent;
+pub use fees::FeeConfig;
+pub use l1x_access_control::Role;
+pub use l1x_eip712::L1X_CHAIN_ID;
+pub use state::STATE_VERSION;
+pub use vesting::VestingSchedule;
+
+use bridge::{Bridge, BridgeIn};
+use compliance::Compliance;
+use snapshots::Snapshots;
+use votes::Checkpoints;
 
 const STORAGE_CONTRACT_KEY: &[u8; 2] = b"aa";
 const STORAGE_BALANCES_KEY: &[u8; 2] = b"ab";
 const STORAGE_ALLOWANCES_KEY: &[u8; 2] = b"ac";
+const STORAGE_ROLES_KEY: &[u8; 2] = b"ad";
+const STORAGE_NONCES_KEY: &[u8; 2] = b"ae";
+const STORAGE_SNAPSHOT_BLOCKS_KEY: &[u8; 2] = b"af";
+const STORAGE_ACCOUNT_SNAPSHOTS_KEY: &[u8; 2] = b"ag";
+const STORAGE_DELEGATES_KEY: &[u8; 2] = b"ah";
+const STORAGE_VOTE_CHECKPOINTS_KEY: &[u8; 2] = b"ai";
+const STORAGE_BLOCKLIST_KEY: &[u8; 2] = b"aj";
+const STORAGE_ALLOWLIST_KEY: &[u8; 2] = b"ak";
+const STORAGE_VESTING_SCHEDULES_KEY: &[u8; 2] = b"al";
+const STORAGE_HOLDERS_KEY: &[u8; 2] = b"am";
+const STORAGE_HOLDER_INDEX_KEY: &[u8; 2] = b"an";
+const STORAGE_BRIDGE_PAYLOADS_KEY: &[u8; 2] = b"ao";
+const STORAGE_BRIDGE_PROCESSED_KEY: &[u8; 2] = b"ap";
 
 #[derive(BorshSerialize, BorshDeserialize, Deserialize)]
 pub struct FTMetadata {
@@ -16,6 +60,7 @@ pub struct FTMetadata {
     decimals: u8,
     symbol: String,
     icon: Option<String>,
+    max_supply: Option<U128>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Default, Clone)]
@@ -48,7 +93,10 @@ impl FTAllowance {
     fn spend(&mut self, spender_id: &Address, amount: u128) {
         match self.spenders.get_mut(spender_id) {
             Some(allowance_amount) => {
-                assert!(*allowance_amount > amount, "The allowance is too small");
+                assert!(
+                    *allowance_amount >= amount,
+                    "The allowance is too small"
+                );
                 *allowance_amount -= amount;
             }
             None => panic!("No allowance for {spender_id}"),
@@ -69,6 +117,26 @@ pub struct L1xFtErc20 {
     balances: LookupMap<Address, u128>,
     allowances: LookupMap<Address, FTAllowance>,
     total_supply: u128,
+    roles: Roles,
+    paused: bool,
+    nonces: LookupMap<Address, u64>,
+    snapshot_id: u64,
+    snapshot_blocks: LookupMap<u64, u64>,
+    account_snapshots: LookupMap<Address, Snapshots>,
+    total_supply_snapshots: Snapshots,
+    delegates: LookupMap<Address, Address>,
+    vote_checkpoints: LookupMap<Address, Checkpoints>,
+    fee_config: FeeConfig,
+    compliance: Compliance,
+    vesting_schedules: LookupMap<u64, VestingSchedule>,
+    next_vesting_id: u64,
+    /// Accounts with a positive balance, in no particular order
+    holders: Vector<Address>,
+    /// Position of every holder in `holders`
+    holder_index: LookupMap<Address, u32>,
+    bridge: Bridge,
+    /// Supply held by accounts missing from the holder index
+    unindexed_supply: u128,
 }
 
 #[contract]
@@ -80,14 +148,101 @@ impl L1xFtErc20 {
             "Only the owner can call this function"
         );
 
-        let mut contract = Self {
+        let mut contract = Self::with_metadata(metadata);
+        contract.grant_owner_roles();
+        contract.initialize_balance_holders(account_ids, amounts);
+        contract.save();
+    }
+
+    /// Upgrades the stored state of an instance deployed with an older
+    /// layout to the current one. Does nothing if it's already current.
+    pub fn migrate() {
+        assert_eq!(
+            caller_address(),
+            contract_owner_address(),
+            "Only the owner can call this function"
+        );
+
+        let (version, payload) = state::read(STORAGE_CONTRACT_KEY)
+            .expect("The contract isn't initialized");
+        if version == STATE_VERSION {
+            l1x_sdk::msg(&format!(
+                "The state is already at version {}",
+                version
+            ));
+            return;
+        }
+
+        let mut contract = state::upgrade(version, payload);
+        l1x_sdk::msg(&format!(
+            "Migrated the state from version {} to {}",
+            version, STATE_VERSION
+        ));
+        contract.save();
+    }
+
+    /// Returns the layout version of the stored state.
+    pub fn ft_state_version() -> u16 {
+        match state::read(STORAGE_CONTRACT_KEY) {
+            Some((version, _)) => version,
+            None => panic!("The contract isn't initialized"),
+        }
+    }
+
+    fn with_metadata(metadata: FTMetadata) -> Self {
+        Self {
             metadata,
             balances: LookupMap::new(STORAGE_BALANCES_KEY.to_vec()),
             allowances: LookupMap::new(STORAGE_ALLOWANCES_KEY.to_vec()),
             total_supply: Default::default(),
-        };
-        contract.initialize_balance_holders(account_ids, amounts);
-        contract.save();
+            roles: Roles::new(STORAGE_ROLES_KEY.to_vec()),
+            paused: false,
+            nonces: LookupMap::new(STORAGE_NONCES_KEY.to_vec()),
+            snapshot_id: 0,
+            snapshot_blocks: LookupMap::new(
+                STORAGE_SNAPSHOT_BLOCKS_KEY.to_vec(),
+            ),
+            account_snapshots: LookupMap::new(
+                STORAGE_ACCOUNT_SNAPSHOTS_KEY.to_vec(),
+            ),
+            total_supply_snapshots: Snapshots::default(),
+            delegates: LookupMap::new(STORAGE_DELEGATES_KEY.to_vec()),
+            vote_checkpoints: LookupMap::new(
+                STORAGE_VOTE_CHECKPOINTS_KEY.to_vec(),
+            ),
+            fee_config: FeeConfig::default(),
+            compliance: Compliance::new(
+                STORAGE_BLOCKLIST_KEY.to_vec(),
+                STORAGE_ALLOWLIST_KEY.to_vec(),
+            ),
+            vesting_schedules: LookupMap::new(
+                STORAGE_VESTING_SCHEDULES_KEY.to_vec(),
+            ),
+            next_vesting_id: 0,
+            holders: Vector::new(STORAGE_HOLDERS_KEY.to_vec()),
+            holder_index: LookupMap::new(STORAGE_HOLDER_INDEX_KEY.to_vec()),
+            bridge: Bridge::new(
+                STORAGE_BRIDGE_PAYLOADS_KEY.to_vec(),
+                STORAGE_BRIDGE_PROCESSED_KEY.to_vec(),
+            ),
+            unindexed_supply: 0,
+        }
+    }
+
+    fn grant_owner_roles(&mut self) {
+        let owner_id = contract_owner_address();
+        for role in [Role::Admin, Role::Minter, Role::Pauser] {
+            Self::emit_role_change(
+                self.roles.internal_grant_role(&owner_id, role, owner_id),
+            );
+        }
+    }
+
+    /// Emits the change of the role members reported by `Roles`, if any.
+    fn emit_role_change(change: Option<RoleChange>) {
+        if let Some(change) = change {
+            emit_event_experimental(FtEvent::from(change));
+        }
     }
 
     fn initialize_balance_holders(&mut self, account_ids: Vec<Address>, amounts: Vec<U128>) {
@@ -103,8 +258,20 @@ impl L1xFtErc20 {
         );
 
         for (account_id, amount) in account_ids.into_iter().zip(amounts) {
-            self.balances.insert(account_id, amount.0);
-            self.total_supply += amount.0;
+            self.set_balance(&account_id, amount.0);
+            let total_supply = self
+                .total_supply
+                .checked_add(amount.0)
+                .expect("total_supply is overflowed");
+            self.assert_within_max_supply(total_supply);
+            self.set_total_supply(total_supply);
+            self.move_voting_power(
+                None,
+                self.delegate_of(&account_id),
+                amount.0,
+            );
+
+            emit_event_experimental(FtEvent::Mint { to: account_id, amount });
         }
     }
 
@@ -123,16 +290,272 @@ impl L1xFtErc20 {
         contract.metadata.decimals
     }
 
+    pub fn ft_max_supply() -> Option<U128> {
+        let contract = Self::load();
+        contract.metadata.max_supply
+    }
+
     pub fn ft_mint(recipient_id: Address, amount: U128) {
-        assert_eq!(
-            caller_address(),
-            contract_owner_address(),
-            "Only the owner can call this function"
+        let mut contract = Self::load();
+
+        contract.roles.assert_role(Role::Minter, &caller_address());
+
+        contract.mint(&recipient_id, amount.0);
+
+        contract.save();
+    }
+
+    /// Mints `amounts` to `recipient_ids`. Each leg is emitted as a `Transfer`
+    /// from the zero address.
+    pub fn ft_airdrop_mint(recipient_ids: Vec<Address>, amounts: Vec<U128>) {
+        let mut contract = Self::load();
+
+        contract.roles.assert_role(Role::Minter, &caller_address());
+
+        let total = Self::batch_total(&recipient_ids, &amounts);
+        let total_supply = contract
+            .total_supply
+            .checked_add(total)
+            .expect("total_supply is overflowed");
+        contract.assert_within_max_supply(total_supply);
+
+        for (recipient_id, amount) in recipient_ids.iter().zip(amounts) {
+            contract.mint_without_event(recipient_id, amount.0);
+
+            emit_event_experimental(FtEvent::Transfer {
+                from: Address::from([0u8; 20]),
+                to: *recipient_id,
+                amount,
+            });
+        }
+
+        contract.save();
+    }
+
+    pub fn ft_grant_role(role: Role, account_id: Address) {
+        let mut contract = Self::load();
+
+        Self::emit_role_change(contract.roles.grant_role(
+            &caller_address(),
+            role,
+            account_id,
+        ));
+
+        contract.save();
+    }
+
+    pub fn ft_revoke_role(role: Role, account_id: Address) {
+        let mut contract = Self::load();
+
+        Self::emit_role_change(contract.roles.revoke_role(
+            &caller_address(),
+            role,
+            account_id,
+        ));
+
+        contract.save();
+    }
+
+    pub fn ft_renounce_role(role: Role) {
+        let mut contract = Self::load();
+
+        Self::emit_role_change(
+            contract.roles.renounce_role(&caller_address(), role),
         );
 
+        contract.save();
+    }
+
+    pub fn ft_has_role(role: Role, account_id: Address) -> bool {
+        let contract = Self::load();
+        contract.roles.has_role(role, &account_id)
+    }
+
+    pub fn ft_pause() {
         let mut contract = Self::load();
+        let account_id = caller_address();
 
-        contract.mint(&recipient_id, amount.0);
+        contract.roles.assert_role(Role::Pauser, &account_id);
+        contract.assert_not_paused();
+        contract.paused = true;
+
+        emit_event_experimental(FtEvent::Paused { account: account_id });
+        l1x_sdk::msg(&format!("Paused by {}", account_id));
+
+        contract.save();
+    }
+
+    pub fn ft_unpause() {
+        let mut contract = Self::load();
+        let account_id = caller_address();
+
+        contract.roles.assert_role(Role::Pauser, &account_id);
+        assert!(contract.paused, "The contract isn't paused");
+        contract.paused = false;
+
+        emit_event_experimental(FtEvent::Unpaused { account: account_id });
+        l1x_sdk::msg(&format!("Unpaused by {}", account_id));
+
+        contract.save();
+    }
+
+    pub fn ft_is_paused() -> bool {
+        let contract = Self::load();
+        contract.paused
+    }
+
+    /// Sets the fee charged on transfers.
+    ///
+    /// - `rate_bps`: fee in basis points of the transferred amount
+    /// - `burn_bps`: share of the fee that is burned, in basis points
+    /// - `treasury`: receiver of the part of the fee that isn't burned
+    pub fn ft_set_fee(rate_bps: u16, burn_bps: u16, treasury: Option<Address>) {
+        let mut contract = Self::load();
+
+        contract.roles.assert_role(Role::Admin, &caller_address());
+        contract.fee_config.set_rates(rate_bps, burn_bps, treasury);
+        l1x_sdk::msg(&format!(
+            "Transfer fee set to {} bps, {} bps of it burned",
+            rate_bps, burn_bps
+        ));
+
+        contract.save();
+    }
+
+    /// Adds `account_ids` to or removes them from the set of accounts that
+    /// don't pay transfer fees, neither as senders nor as recipients.
+    pub fn ft_set_fee_exempt(account_ids: Vec<Address>, exempt: bool) {
+        let mut contract = Self::load();
+
+        contract.roles.assert_role(Role::Admin, &caller_address());
+        for account_id in account_ids {
+            contract.fee_config.set_exempt(account_id, exempt);
+        }
+
+        contract.save();
+    }
+
+    pub fn ft_fee_config() -> FeeConfig {
+        let contract = Self::load();
+        contract.fee_config
+    }
+
+    pub fn ft_set_compliance_mode(mode: ComplianceMode) {
+        let mut contract = Self::load();
+        let account_id = caller_address();
+
+        contract.roles.assert_role(Role::Admin, &account_id);
+        contract.compliance.set_mode(mode);
+
+        emit_event_experimental(FtEvent::ComplianceModeChanged {
+            mode,
+            account: account_id,
+        });
+        l1x_sdk::msg(&format!(
+            "Compliance mode set to {:?} by {}",
+            mode, account_id
+        ));
+
+        contract.save();
+    }
+
+    pub fn ft_compliance_mode() -> ComplianceMode {
+        let contract = Self::load();
+        contract.compliance.mode()
+    }
+
+    /// Adds `account_ids` to or removes them from the blocklist.
+    pub fn ft_set_blocklisted(account_ids: Vec<Address>, blocklisted: bool) {
+        let mut contract = Self::load();
+
+        contract.roles.assert_role(Role::Admin, &caller_address());
+        for account_id in account_ids {
+            contract.compliance.set_blocklisted(account_id, blocklisted);
+            l1x_sdk::msg(&format!(
+                "Blocklisted {}: {}",
+                account_id, blocklisted
+            ));
+        }
+
+        contract.save();
+    }
+
+    /// Adds `account_ids` to or removes them from the allowlist.
+    pub fn ft_set_allowlisted(account_ids: Vec<Address>, allowlisted: bool) {
+        let mut contract = Self::load();
+
+        contract.roles.assert_role(Role::Admin, &caller_address());
+        for account_id in account_ids {
+            contract.compliance.set_allowlisted(account_id, allowlisted);
+            l1x_sdk::msg(&format!(
+                "Allowlisted {}: {}",
+                account_id, allowlisted
+            ));
+        }
+
+        contract.save();
+    }
+
+    pub fn ft_is_blocklisted(account_id: Address) -> bool {
+        let contract = Self::load();
+        contract.compliance.is_blocklisted(&account_id)
+    }
+
+    pub fn ft_is_allowlisted(account_id: Address) -> bool {
+        let contract = Self::load();
+        contract.compliance.is_allowlisted(&account_id)
+    }
+
+    /// Returns why a transfer of `amount` from `sender_id` to `recipient_id`
+    /// would fail, or `TransferRestriction::Ok` if it wouldn't.
+    pub fn ft_can_transfer(
+        sender_id: Address,
+        recipient_id: Address,
+        amount: U128,
+    ) -> TransferRestriction {
+        let contract = Self::load();
+
+        if contract.paused {
+            return TransferRestriction::Paused;
+        }
+        if Self::is_escrow(&recipient_id) {
+            return TransferRestriction::RecipientIsContract;
+        }
+
+        match contract
+            .compliance
+            .transfer_restriction(&sender_id, &recipient_id)
+        {
+            TransferRestriction::Ok
+                if contract.balance_of(&sender_id).unwrap_or_default()
+                    < amount.0 =>
+            {
+                TransferRestriction::InsufficientBalance
+            }
+            restriction => restriction,
+        }
+    }
+
+    pub fn ft_burn(amount: U128) {
+        let mut contract = Self::load();
+
+        let account_id = caller_address();
+        contract.burn(&account_id, amount.0);
+
+        contract.save();
+    }
+
+    pub fn ft_burn_from(owner_id: Address, amount: U128) {
+        let mut contract = Self::load();
+        let spender_id = caller_address();
+
+        contract.allowance_update(
+            AllowanceUpdateOp::Spend,
+            &owner_id,
+            &spender_id,
+            amount.0,
+        );
+        contract.burn(&owner_id, amount.0);
 
         contract.save();
     }
@@ -146,6 +569,64 @@ impl L1xFtErc20 {
         contract.save()
     }
 
+    pub fn ft_batch_transfer(recipient_ids: Vec<Address>, amounts: Vec<U128>) {
+        let mut contract = Self::load();
+
+        let sender_id = caller_address();
+        let total = Self::batch_total(&recipient_ids, &amounts);
+        assert!(
+            contract.balance_of(&sender_id).unwrap_or_default() >= total,
+            "Not enough balance to transfer"
+        );
+
+        for (recipient_id, amount) in recipient_ids.iter().zip(amounts) {
+            contract.transfer(&sender_id, recipient_id, amount.0);
+        }
+
+        contract.save();
+    }
+
+    /// Transfers `amount` to the `receiver_id` contract and calls its
+    /// `ft_on_transfer(sender_id, amount, msg)` method with the amount
+    /// received after the transfer fee. The receiver returns the amount it
+    /// didn't use, which is refunded to the caller without a fee. Returns the
+    /// amount that was finally kept by the receiver.
+    pub fn ft_transfer_call(
+        receiver_id: Address,
+        amount: U128,
+        msg: String,
+    ) -> U128 {
+        let sender_id = caller_address();
+
+        // Persist the transfer before handing control to the receiver, which
+        // may call back into this contract
+        let received_amount = {
+            let mut contract = Self::load();
+            let received_amount =
+                contract.transfer(&sender_id, &receiver_id, amount.0);
+            contract.save();
+            received_amount
+        };
+
+        let unused_amount = Self::call_on_transfer(
+            &sender_id,
+            &receiver_id,
+            received_amount,
+            msg,
+        );
+
+        let mut contract = Self::load();
+        let refunded_amount = contract.resolve_transfer(
+            &sender_id,
+            &receiver_id,
+            received_amount,
+            unused_amount,
+        );
+        contract.save();
+
+        (received_amount - refunded_amount).into()
+    }
+
     pub fn ft_transfer_from(sender_id: Address, recipient_id: Address, amount: U128) {
         let mut contract = Self::load();
         let spender_id = caller_address();
@@ -166,6 +647,106 @@ impl L1xFtErc20 {
         contract.balance_of(&account_id).unwrap_or_default().into()
     }
 
+    /// Panics until every holder of a migrated token is indexed, see
+    /// `ft_index_holders`.
+    pub fn ft_holder_count() -> u32 {
+        let contract = Self::load();
+        contract.assert_holders_indexed();
+        contract.holders.len()
+    }
+
+    /// Returns up to `limit` holders and their balances, starting at
+    /// position `from_index` of the holder index. The order changes when
+    /// holders come and go. See `ft_holder_count` for migrated tokens.
+    pub fn ft_holders(from_index: u32, limit: u32) -> Vec<(Address, U128)> {
+        let contract = Self::load();
+        contract.assert_holders_indexed();
+
+        let end = from_index.saturating_add(limit).min(contract.holders.len());
+        (from_index..end)
+            .map(|idx| {
+                let account_id =
+                    *contract.holders.get(idx).expect("Can't get a holder");
+                let balance =
+                    contract.balance_of(&account_id).unwrap_or_default();
+                (account_id, balance.into())
+            })
+            .collect()
+    }
+
+    /// Returns the `limit` largest holders and their balances, largest
+    /// first. Reads every holder, so it's meant for off-chain queries. See
+    /// `ft_holder_count` for migrated tokens.
+    pub fn ft_top_holders(limit: u32) -> Vec<(Address, U128)> {
+        let contract = Self::load();
+        contract.assert_holders_indexed();
+
+        let mut holders: Vec<(Address, u128)> = (0..contract.holders.len())
+            .map(|idx| {
+                let account_id =
+                    *contract.holders.get(idx).expect("Can't get a holder");
+                (
+                    account_id,
+                    contract.balance_of(&account_id).unwrap_or_default(),
+                )
+            })
+            .collect();
+        holders.sort_by(|(a_id, a_balance), (b_id, b_balance)| {
+            b_balance.cmp(a_balance).then_with(|| a_id.cmp(b_id))
+        });
+
+        holders
+            .into_iter()
+            .take(limit as usize)
+            .map(|(account_id, balance)| (account_id, balance.into()))
+            .collect()
+    }
+
+    /// Adds the holders among `account_ids` that are missing from the holder
+    /// index, which is empty after a migration from layout 0. The balances
+    /// can't be enumerated, so the accounts are found off-chain, e.g. from
+    /// the `Transfer` logs. The holder views work again once
+    /// `ft_unindexed_supply` is zero.
+    pub fn ft_index_holders(account_ids: Vec<Address>) {
+        let mut contract = Self::load();
+
+        contract.roles.assert_role(Role::Admin, &caller_address());
+        for account_id in account_ids {
+            let balance = contract.balance_of(&account_id).unwrap_or_default();
+            contract.update_holders(&account_id, balance, balance);
+        }
+
+        contract.save();
+    }
+
+    /// Returns the supply held by accounts missing from the holder index.
+    pub fn ft_unindexed_supply() -> U128 {
+        let contract = Self::load();
+        contract.unindexed_supply.into()
+    }
+
+    /// Returns up to `limit` non-zero allowances granted by `owner_id`,
+    /// ordered by spender, skipping the first `from_index` ones.
+    pub fn ft_allowances_of(
+        owner_id: Address,
+        from_index: u32,
+        limit: u32,
+    ) -> Vec<(Address, U128)> {
+        let contract = Self::load();
+
+        match contract.allowances.get(&owner_id) {
+            Some(allowance) => allowance
+                .spenders
+                .iter()
+                .filter(|(_, amount)| **amount > 0)
+                .skip(from_index as usize)
+                .take(limit as usize)
+                .map(|(spender_id, amount)| (*spender_id, (*amount).into()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
     pub fn ft_approve(spender_id: Address, amount: U128) {
         let mut contract = Self::load();
         let owner_id = caller_address();
@@ -206,41 +787,781 @@ impl L1xFtErc20 {
         contract.save();
     }
 
-    pub fn ft_allowance(owner_id: Address, spender_id: Address) -> U128 {
-        let contract = Self::load();
+    /// Sets the allowance of `spender_id` on behalf of `owner_id`, who
+    /// authorized it by signing the digest returned by `ft_permit_digest`.
+    ///
+    /// - `deadline`: last `l1x_sdk::block_timestamp` the permit is valid at
+    /// - `nonce`: the current `ft_nonces` value of `owner_id`
+    /// - `signature`: hex encoded secp256k1 signature of the digest
+    pub fn ft_permit(
+        owner_id: Address,
+        spender_id: Address,
+        amount: U128,
+        deadline: U64,
+        nonce: U64,
+        signature: String,
+    ) {
+        let mut contract = Self::load();
 
-        match contract.allowances.get(&owner_id) {
-            Some(allowance) => allowance.get(&spender_id).into(),
-            None => 0.into(),
-        }
-    }
+        assert!(
+            l1x_sdk::block_timestamp() <= deadline.0,
+            "The permit has expired"
+        );
 
-    fn mint(&mut self, recipient_id: &Address, amount: u128) {
-        let receiver_balance = self.balance_of(&recipient_id).unwrap_or_default();
+        let current_nonce = contract.nonce_of(&owner_id);
+        assert_eq!(nonce.0, current_nonce, "Invalid permit nonce");
 
-        let total_supply = self
-            .total_supply
-            .checked_add(amount)
-            .expect("total_supply is overflowed");
-        self.total_supply = total_supply;
-        self.balances
-            .insert(recipient_id.clone(), receiver_balance + amount);
+        let permit = eip712::Permit {
+            owner_id: &owner_id,
+            spender_id: &spender_id,
+            amount: amount.0,
+            nonce: nonce.0,
+            deadline: deadline.0,
+        };
+        let digest = permit
+            .digest(&contract.metadata.name, &contract_instance_address());
+        let signer = l1x_eip712::recover_signer(digest, &signature);
+        assert_eq!(signer, owner_id, "Invalid permit signature");
 
-        l1x_sdk::msg(&format!("Minted {} tokens for {}", amount, recipient_id));
-    }
+        contract.nonces.insert(owner_id.clone(), current_nonce + 1);
 
-    fn transfer(&mut self, sender_id: &Address, recipient_id: &Address, amount: u128) {
-        let sender_balance = self.balance_of(&sender_id).unwrap_or_default();
-        let receiver_balance = self.balance_of(&recipient_id).unwrap_or_default();
-        assert!(sender_balance >= amount, "Not enough balance to transfer");
-        self.balances
-            .insert(sender_id.clone(), sender_balance - amount);
-        self.balances
-            .insert(recipient_id.clone(), receiver_balance + amount);
-        l1x_sdk::msg(&format!(
-            "Transferred {} tokens from {} to {}",
-            amount, sender_id, recipient_id
-        ));
+        contract.assert_if_no_balance(&owner_id);
+        contract.allowance_update(
+            AllowanceUpdateOp::Set,
+            &owner_id,
+            &spender_id,
+            amount.0,
+        );
+
+        contract.save();
+    }
+
+    /// Returns the hex encoded digest `owner_id` has to sign for `ft_permit`
+    pub fn ft_permit_digest(
+        owner_id: Address,
+        spender_id: Address,
+        amount: U128,
+        deadline: U64,
+        nonce: U64,
+    ) -> String {
+        let contract = Self::load();
+
+        let permit = eip712::Permit {
+            owner_id: &owner_id,
+            spender_id: &spender_id,
+            amount: amount.0,
+            nonce: nonce.0,
+            deadline: deadline.0,
+        };
+        hex::encode(
+            permit
+                .digest(&contract.metadata.name, &contract_instance_address()),
+        )
+    }
+
+    pub fn ft_nonces(owner_id: Address) -> U64 {
+        let contract = Self::load();
+        contract.nonce_of(&owner_id).into()
+    }
+
+    /// Takes a new snapshot of all balances and the total supply at the current
+    /// block and returns its id
+    pub fn ft_snapshot() -> U64 {
+        let mut contract = Self::load();
+
+        contract.roles.assert_role(Role::Admin, &caller_address());
+
+        contract.snapshot_id += 1;
+        let block_number = l1x_sdk::block_number();
+        contract.snapshot_blocks.insert(contract.snapshot_id, block_number);
+
+        emit_event_experimental(FtEvent::Snapshot {
+            id: contract.snapshot_id.into(),
+            block_number: block_number.into(),
+        });
+
+        let snapshot_id = contract.snapshot_id;
+        contract.save();
+
+        snapshot_id.into()
+    }
+
+    /// Returns the block number `snapshot_id` was taken at
+    pub fn ft_snapshot_block(snapshot_id: U64) -> U64 {
+        let contract = Self::load();
+
+        contract.assert_valid_snapshot(snapshot_id.0);
+        contract
+            .snapshot_blocks
+            .get(&snapshot_id.0)
+            .copied()
+            .unwrap_or_default()
+            .into()
+    }
+
+    pub fn ft_balance_of_at(account_id: Address, snapshot_id: U64) -> U128 {
+        let contract = Self::load();
+
+        contract.assert_valid_snapshot(snapshot_id.0);
+        contract
+            .account_snapshots
+            .get(&account_id)
+            .and_then(|snapshots| snapshots.value_at(snapshot_id.0))
+            .unwrap_or_else(|| {
+                contract.balance_of(&account_id).unwrap_or_default()
+            })
+            .into()
+    }
+
+    pub fn ft_total_supply_at(snapshot_id: U64) -> U128 {
+        let contract = Self::load();
+
+        contract.assert_valid_snapshot(snapshot_id.0);
+        contract
+            .total_supply_snapshots
+            .value_at(snapshot_id.0)
+            .unwrap_or(contract.total_supply)
+            .into()
+    }
+
+    /// Delegates the voting power of the caller's tokens to `delegatee`
+    pub fn ft_delegate(delegatee: Address) {
+        let mut contract = Self::load();
+
+        let delegator = caller_address();
+        contract.delegate(&delegator, delegatee);
+
+        contract.save();
+    }
+
+    /// Delegates on behalf of the signer of the digest returned by
+    /// `ft_delegation_digest`.
+    ///
+    /// - `nonce`: the current `ft_nonces` value of the signer
+    /// - `expiry`: last `l1x_sdk::block_timestamp` the signature is valid at
+    /// - `signature`: hex encoded secp256k1 signature of the digest
+    pub fn ft_delegate_by_sig(
+        delegatee: Address,
+        nonce: U64,
+        expiry: U64,
+        signature: String,
+    ) {
+        let mut contract = Self::load();
+
+        assert!(
+            l1x_sdk::block_timestamp() <= expiry.0,
+            "The signature has expired"
+        );
+
+        let delegation = eip712::Delegation {
+            delegatee: &delegatee,
+            nonce: nonce.0,
+            expiry: expiry.0,
+        };
+        let digest = delegation
+            .digest(&contract.metadata.name, &contract_instance_address());
+        let delegator = l1x_eip712::recover_signer(digest, &signature);
+
+        let current_nonce = contract.nonce_of(&delegator);
+        assert_eq!(nonce.0, current_nonce, "Invalid delegation nonce");
+        contract.nonces.insert(delegator.clone(), current_nonce + 1);
+
+        contract.delegate(&delegator, delegatee);
+
+        contract.save();
+    }
+
+    /// Returns the hex encoded digest to sign for `ft_delegate_by_sig`
+    pub fn ft_delegation_digest(
+        delegatee: Address,
+        nonce: U64,
+        expiry: U64,
+    ) -> String {
+        let contract = Self::load();
+
+        let delegation = eip712::Delegation {
+            delegatee: &delegatee,
+            nonce: nonce.0,
+            expiry: expiry.0,
+        };
+        hex::encode(
+            delegation
+                .digest(&contract.metadata.name, &contract_instance_address()),
+        )
+    }
+
+    pub fn ft_delegates(account_id: Address) -> Option<Address> {
+        let contract = Self::load();
+        contract.delegate_of(&account_id)
+    }
+
+    pub fn ft_get_votes(account_id: Address) -> U128 {
+        let contract = Self::load();
+
+        contract
+            .vote_checkpoints
+            .get(&account_id)
+            .map(|checkpoints| checkpoints.latest())
+            .unwrap_or_default()
+            .into()
+    }
+
+    /// Returns the votes of `account_id` at the end of `block_number`, which
+    /// must already be mined
+    pub fn ft_get_past_votes(account_id: Address, block_number: U64) -> U128 {
+        let contract = Self::load();
+
+        assert!(
+            block_number.0 < l1x_sdk::block_number(),
+            "Block {} is not yet mined",
+            block_number.0
+        );
+        contract
+            .vote_checkpoints
+            .get(&account_id)
+            .map(|checkpoints| checkpoints.votes_at(block_number.0))
+            .unwrap_or_default()
+            .into()
+    }
+
+    /// Moves `amount` tokens of the caller into escrow and vests them to
+    /// `beneficiary`. Returns the id of the schedule.
+    ///
+    /// - `start`: `l1x_sdk::block_timestamp` the vesting starts at
+    /// - `cliff`: time after `start` before which nothing can be released
+    /// - `duration`: time after `start` at which everything is vested
+    /// - `revocable`: whether an admin can revoke the unvested part
+    pub fn ft_vesting_create(
+        beneficiary: Address,
+        amount: U128,
+        start: U64,
+        cliff: U64,
+        duration: U64,
+        revocable: bool,
+    ) -> U64 {
+        let mut contract = Self::load();
+        let funder = caller_address();
+
+        contract.roles.assert_role(Role::Admin, &funder);
+        contract.compliance.assert_permitted(&beneficiary);
+        let schedule = VestingSchedule::new(
+            beneficiary,
+            funder,
+            amount.0,
+            start.0,
+            cliff.0,
+            duration.0,
+            revocable,
+        );
+        contract.transfer_without_fee(
+            &funder,
+            &contract_instance_address(),
+            amount.0,
+        );
+
+        let id = contract.next_vesting_id;
+        contract.next_vesting_id += 1;
+        contract.vesting_schedules.insert(id, schedule);
+
+        emit_event_experimental(FtEvent::VestingCreated {
+            id: id.into(),
+            beneficiary,
+            amount,
+        });
+
+        contract.save();
+
+        id.into()
+    }
+
+    /// Transfers the vested and not yet released tokens of schedule `id` to
+    /// its beneficiary. Anyone can call it. Returns the released amount.
+    ///
+    /// Panics if the compliance mode no longer permits the beneficiary.
+    pub fn ft_vesting_release(id: U64) -> U128 {
+        let mut contract = Self::load();
+        let now = l1x_sdk::block_timestamp();
+
+        let schedule = contract.vesting_schedule(id.0).clone();
+        let amount = schedule.releasable_amount(now);
+        assert!(amount > 0, "No tokens to release");
+        contract.compliance.assert_permitted(&schedule.beneficiary);
+
+        contract.transfer_without_fee(
+            &contract_instance_address(),
+            &schedule.beneficiary,
+            amount,
+        );
+        contract
+            .vesting_schedules
+            .get_mut(&id.0)
+            .expect("Nonexistent vesting schedule")
+            .released = (schedule.released.0 + amount).into();
+
+        emit_event_experimental(FtEvent::VestingReleased {
+            id,
+            beneficiary: schedule.beneficiary,
+            amount: amount.into(),
+        });
+
+        contract.save();
+
+        amount.into()
+    }
+
+    /// Stops schedule `id` and returns its unvested tokens to the funder. The
+    /// beneficiary can still release what vested before the revocation.
+    pub fn ft_vesting_revoke(id: U64) {
+        let mut contract = Self::load();
+        let now = l1x_sdk::block_timestamp();
+
+        contract.roles.assert_role(Role::Admin, &caller_address());
+        let schedule = contract
+            .vesting_schedules
+            .get_mut(&id.0)
+            .unwrap_or_else(|| panic!("Nonexistent vesting schedule {}", id.0));
+        let unvested_amount = schedule.revoke(now);
+        let funder = schedule.funder;
+
+        if unvested_amount > 0 {
+            contract.transfer_without_fee(
+                &contract_instance_address(),
+                &funder,
+                unvested_amount,
+            );
+        }
+
+        emit_event_experimental(FtEvent::VestingRevoked {
+            id,
+            refunded: unvested_amount.into(),
+        });
+
+        contract.save();
+    }
+
+    pub fn ft_vesting_schedule(id: U64) -> VestingSchedule {
+        let contract = Self::load();
+        contract.vesting_schedule(id.0).clone()
+    }
+
+    pub fn ft_vesting_vested_amount(id: U64) -> U128 {
+        let contract = Self::load();
+        contract
+            .vesting_schedule(id.0)
+            .vested_amount(l1x_sdk::block_timestamp())
+            .into()
+    }
+
+    pub fn ft_vesting_releasable_amount(id: U64) -> U128 {
+        let contract = Self::load();
+        contract
+            .vesting_schedule(id.0)
+            .releasable_amount(l1x_sdk::block_timestamp())
+            .into()
+    }
+
+    /// Switches between burning and locking bridged tokens. Locked tokens
+    /// must be bridged back first.
+    pub fn ft_set_bridge_mode(mode: BridgeMode) {
+        let mut contract = Self::load();
+
+        contract.roles.assert_role(Role::Admin, &caller_address());
+        assert_eq!(
+            contract.bridge.locked, 0,
+            "Can't switch the bridge mode while tokens are locked"
+        );
+        contract.bridge.mode = mode;
+        l1x_sdk::msg(&format!("Bridge mode set to {:?}", mode));
+
+        contract.save();
+    }
+
+    /// Sets the bridge contract of `network`, or removes the network if
+    /// `contract_address` is `None`.
+    pub fn ft_set_bridge_remote(
+        network: String,
+        contract_address: Option<Address>,
+    ) {
+        let mut contract = Self::load();
+
+        contract.roles.assert_role(Role::Admin, &caller_address());
+        l1x_sdk::msg(&format!(
+            "Bridge contract of {} set to {:?}",
+            network, contract_address
+        ));
+        match contract_address {
+            Some(contract_address) => {
+                contract.bridge.remotes.insert(network, contract_address)
+            }
+            None => contract.bridge.remotes.remove(&network),
+        };
+
+        contract.save();
+    }
+
+    pub fn ft_bridge_mode() -> BridgeMode {
+        let contract = Self::load();
+        contract.bridge.mode
+    }
+
+    pub fn ft_bridge_remote(network: String) -> Option<Address> {
+        let contract = Self::load();
+        contract.bridge.remotes.get(&network).copied()
+    }
+
+    pub fn ft_bridge_locked() -> U128 {
+        let contract = Self::load();
+        contract.bridge.locked.into()
+    }
+
+    /// Burns or locks `amount` tokens of the caller to release them to
+    /// `dest_address` on `dest_network`. Returns the hex encoded global tx
+    /// id of the outbound payload.
+    pub fn ft_bridge_out(
+        dest_network: String,
+        dest_address: Address,
+        amount: U128,
+    ) -> String {
+        let mut contract = Self::load();
+        let sender_id = caller_address();
+        let instance_address = contract_instance_address();
+
+        assert!(amount.0 > 0, "Nothing to bridge");
+        contract.compliance.assert_permitted(&sender_id);
+        match contract.bridge.mode {
+            BridgeMode::BurnMint => contract.burn(&sender_id, amount.0),
+            BridgeMode::LockUnlock => {
+                contract.transfer_without_fee(
+                    &sender_id,
+                    &instance_address,
+                    amount.0,
+                );
+                contract.bridge.locked += amount.0;
+            }
+        }
+
+        let payload = contract.bridge.record_out(
+            &instance_address,
+            sender_id,
+            dest_network,
+            dest_address,
+            amount.0,
+        );
+
+        emit_event_experimental(FtEvent::BridgeOut {
+            global_tx_id: payload.global_tx_id,
+            sender: sender_id,
+            dest_network: payload.dest_network,
+            dest_address,
+            amount,
+        });
+
+        contract.save();
+
+        hex::encode(payload.global_tx_id)
+    }
+
+    pub fn ft_bridge_payload(global_tx_id: String) -> BridgeOutPayload {
+        let contract = Self::load();
+        contract
+            .bridge
+            .payload(&bridge::parse_global_tx_id(&global_tx_id))
+            .clone()
+    }
+
+    /// Retrieve payload hash to sign
+    ///
+    /// - `global_tx_id`: Global transaction identifier returned by
+    ///   `ft_bridge_out`
+    pub fn get_payload_hash_to_sign(global_tx_id: String) -> String {
+        let contract = Self::load();
+        let payload =
+            contract.bridge.payload(&bridge::parse_global_tx_id(&global_tx_id));
+
+        hex::encode(payload.hash_to_sign(&contract_instance_address()))
+    }
+
+    /// Save event to contract storage
+    ///
+    /// - `global_tx_id`: Global transaction identifier
+    /// - `source_id`: Source Identifier, 0 for `BridgeLocked` events
+    /// - `event_data`: Base64 encoded JSON of the ethers `Log`
+    pub fn save_event_data(
+        global_tx_id: String,
+        source_id: U64,
+        event_data: String,
+    ) {
+        match source_id.0 {
+            0 => Self::ft_bridge_in(global_tx_id, event_data),
+            _ => panic!("Unknown source id: {}", source_id.0),
+        }
+    }
+
+    /// Mints (or unlocks) the tokens of a `BridgeLocked` log emitted by a
+    /// remote bridge contract. Each global tx id of the log is processed only
+    /// once.
+    ///
+    /// - `global_tx_id`: x-talk global transaction identifier, the one of the
+    ///   log
+    /// - `event_data`: Base64 encoded JSON of the ethers `Log`
+    pub fn ft_bridge_in(global_tx_id: String, event_data: String) {
+        let mut contract = Self::load();
+
+        contract.roles.assert_role(Role::Minter, &caller_address());
+        let event_data = match STANDARD.decode(event_data.as_bytes()) {
+            Ok(data) => data,
+            Err(_) => panic!("Can't decode base64 event_data"),
+        };
+        let bridge_in = BridgeIn::from_log(&event_data);
+        assert!(
+            bridge::parse_global_tx_id(&global_tx_id) == bridge_in.global_tx_id,
+            "The global tx id {} doesn't match the log",
+            global_tx_id
+        );
+
+        contract.assert_not_escrow(&bridge_in.recipient);
+        contract.bridge.accept_in(&bridge_in);
+        contract.compliance.assert_permitted(&bridge_in.recipient);
+        match contract.bridge.mode {
+            BridgeMode::BurnMint => {
+                contract.mint(&bridge_in.recipient, bridge_in.amount)
+            }
+            BridgeMode::LockUnlock => {
+                assert!(
+                    contract.bridge.locked >= bridge_in.amount,
+                    "Not enough locked tokens to unlock"
+                );
+                contract.bridge.locked -= bridge_in.amount;
+                contract.transfer_without_fee(
+                    &contract_instance_address(),
+                    &bridge_in.recipient,
+                    bridge_in.amount,
+                );
+            }
+        }
+
+        emit_event_experimental(FtEvent::BridgeIn {
+            global_tx_id: bridge_in.global_tx_id,
+            recipient: bridge_in.recipient,
+            amount: bridge_in.amount.into(),
+        });
+        l1x_sdk::msg(&format!("Processed bridge transaction {}", global_tx_id));
+
+        contract.save();
+    }
+
+    pub fn ft_bridge_is_processed(global_tx_id: String) -> bool {
+        let contract = Self::load();
+        contract.bridge.is_processed(&bridge::parse_global_tx_id(&global_tx_id))
+    }
+
+    pub fn ft_allowance(owner_id: Address, spender_id: Address) -> U128 {
+        let contract = Self::load();
+        contract.allowance_of(&owner_id, &spender_id).into()
+    }
+
+    fn mint(&mut self, recipient_id: &Address, amount: u128) {
+        self.mint_without_event(recipient_id, amount);
+
+        emit_event_experimental(FtEvent::Mint {
+            to: *recipient_id,
+            amount: amount.into(),
+        });
+    }
+
+    fn mint_without_event(&mut self, recipient_id: &Address, amount: u128) {
+        self.assert_not_paused();
+        self.assert_not_escrow(recipient_id);
+        self.compliance.assert_permitted(recipient_id);
+
+        let receiver_balance = self.balance_of(&recipient_id).unwrap_or_default();
+
+        let total_supply = self
+            .total_supply
+            .checked_add(amount)
+            .expect("total_supply is overflowed");
+        self.assert_within_max_supply(total_supply);
+        self.set_total_supply(total_supply);
+        self.set_balance(recipient_id, receiver_balance + amount);
+        self.move_voting_power(None, self.delegate_of(recipient_id), amount);
+
+        l1x_sdk::msg(&format!("Minted {} tokens for {}", amount, recipient_id));
+    }
+
+    /// Validates the batch arguments and returns the sum of `amounts`
+    fn batch_total(recipient_ids: &[Address], amounts: &[U128]) -> u128 {
+        assert_eq!(
+            recipient_ids.len(),
+            amounts.len(),
+            "recipient_ids and amounts length mismatch"
+        );
+
+        amounts.iter().fold(0u128, |total, amount| {
+            total.checked_add(amount.0).expect("Batch total is overflowed")
+        })
+    }
+
+    fn burn(&mut self, account_id: &Address, amount: u128) {
+        self.assert_not_paused();
+
+        let balance = self.balance_of(account_id).unwrap_or_default();
+        assert!(balance >= amount, "Not enough balance to burn");
+
+        self.set_balance(account_id, balance - amount);
+        self.set_total_supply(self.total_supply - amount);
+        self.move_voting_power(self.delegate_of(account_id), None, amount);
+
+        emit_event_experimental(FtEvent::Burn {
+            from: *account_id,
+            amount: amount.into(),
+        });
+
+        l1x_sdk::msg(&format!("Burned {} tokens from {}", amount, account_id));
+    }
+
+    /// Transfers `amount` minus the transfer fee to `recipient_id` and
+    /// charges the fee to `sender_id`. Returns the amount received.
+    fn transfer(
+        &mut self,
+        sender_id: &Address,
+        recipient_id: &Address,
+        amount: u128,
+    ) -> u128 {
+        self.assert_not_escrow(recipient_id);
+        self.compliance.assert_permitted(sender_id);
+        self.compliance.assert_permitted(recipient_id);
+
+        let fee = self.fee_config.fee_for(sender_id, recipient_id, amount);
+        if fee.total() == 0 {
+            self.transfer_without_fee(sender_id, recipient_id, amount);
+            return amount;
+        }
+
+        let sender_balance = self.balance_of(&sender_id).unwrap_or_default();
+        assert!(sender_balance >= amount, "Not enough balance to transfer");
+
+        let net_amount = amount - fee.total();
+        self.transfer_without_fee(sender_id, recipient_id, net_amount);
+        if let Some(treasury) = fee.treasury.filter(|_| fee.treasury_amount > 0)
+        {
+            self.transfer_without_fee(
+                sender_id,
+                &treasury,
+                fee.treasury_amount,
+            );
+        }
+        if fee.burn_amount > 0 {
+            self.burn(sender_id, fee.burn_amount);
+        }
+
+        emit_event_experimental(FtEvent::TransferFee {
+            from: *sender_id,
+            to: *recipient_id,
+            gross: amount.into(),
+            fee: fee.total().into(),
+            burned: fee.burn_amount.into(),
+            net: net_amount.into(),
+        });
+
+        net_amount
+    }
+
+    fn transfer_without_fee(
+        &mut self,
+        sender_id: &Address,
+        recipient_id: &Address,
+        amount: u128,
+    ) {
+        self.assert_not_paused();
+
+        let sender_balance = self.balance_of(&sender_id).unwrap_or_default();
+        assert!(sender_balance >= amount, "Not enough balance to transfer");
+        self.set_balance(sender_id, sender_balance - amount);
+
+        // Read after the debit so that a transfer to oneself is a no-op
+        let receiver_balance =
+            self.balance_of(&recipient_id).unwrap_or_default();
+        self.set_balance(recipient_id, receiver_balance + amount);
+
+        self.move_voting_power(
+            self.delegate_of(sender_id),
+            self.delegate_of(recipient_id),
+            amount,
+        );
+
+        emit_event_experimental(FtEvent::Transfer {
+            from: *sender_id,
+            to: *recipient_id,
+            amount: amount.into(),
+        });
+
+        l1x_sdk::msg(&format!(
+            "Transferred {} tokens from {} to {}",
+            amount, sender_id, recipient_id
+        ));
+    }
+
+    /// Calls `ft_on_transfer` on the receiver and returns the unused amount.
+    /// A failed call or an unreadable result refunds everything.
+    fn call_on_transfer(
+        sender_id: &Address,
+        receiver_id: &Address,
+        amount: u128,
+        msg: String,
+    ) -> u128 {
+        let args = {
+            #[derive(Serialize)]
+            struct Args {
+                sender_id: Address,
+                amount: U128,
+                msg: String,
+            }
+            Args { sender_id: sender_id.clone(), amount: amount.into(), msg }
+        };
+
+        let call = ContractCall {
+            contract_address: receiver_id.clone(),
+            method_name: "ft_on_transfer".to_string(),
+            args: serde_json::to_vec(&args).unwrap(),
+            read_only: false,
+            fee_limit: 12,
+        };
+
+        match l1x_sdk::call_contract(&call) {
+            Some(res) => match serde_json::from_slice::<U128>(&res) {
+                Ok(unused_amount) => unused_amount.0,
+                Err(error) => {
+                    l1x_sdk::msg(&format!(
+                        "ft_on_transfer returned an invalid result: {}",
+                        error
+                    ));
+                    amount
+                }
+            },
+            None => {
+                l1x_sdk::msg("ft_on_transfer failed");
+                amount
+            }
+        }
+    }
+
+    /// Refunds the unused part of a `ft_transfer_call`, limited by what the
+    /// receiver still holds. Returns the refunded amount.
+    fn resolve_transfer(
+        &mut self,
+        sender_id: &Address,
+        receiver_id: &Address,
+        amount: u128,
+        unused_amount: u128,
+    ) -> u128 {
+        let receiver_balance = self.balance_of(receiver_id).unwrap_or_default();
+        let refund_amount = unused_amount.min(amount).min(receiver_balance);
+
+        if refund_amount > 0 {
+            self.transfer_without_fee(receiver_id, sender_id, refund_amount);
+        }
+
+        refund_amount
     }
 
     fn allowance_update(
@@ -250,6 +1571,14 @@ impl L1xFtErc20 {
         spender_id: &Address,
         amount: u128,
     ) {
+        self.assert_not_paused();
+        // Restricted accounts may still lower the allowances they granted
+        if !matches!(update_op, AllowanceUpdateOp::Decrease) {
+            self.compliance.assert_permitted(owner_id);
+            self.compliance.assert_permitted(spender_id);
+        }
+
+        let old_amount = self.allowance_of(owner_id, spender_id);
         let allowance = self.allowances.get_mut(owner_id);
 
         match update_op {
@@ -278,12 +1607,247 @@ impl L1xFtErc20 {
                 None => panic!("{owner_id} didn't set allowance for {spender_id}"),
             },
         }
+
+        emit_event_experimental(FtEvent::Approval {
+            owner: *owner_id,
+            spender: *spender_id,
+            old: old_amount.into(),
+            new: self.allowance_of(owner_id, spender_id).into(),
+        });
+    }
+
+    fn allowance_of(&self, owner_id: &Address, spender_id: &Address) -> u128 {
+        match self.allowances.get(owner_id) {
+            Some(allowance) => allowance.get(spender_id),
+            None => 0,
+        }
+    }
+
+    fn nonce_of(&self, owner_id: &Address) -> u64 {
+        self.nonces.get(owner_id).copied().unwrap_or_default()
+    }
+
+    /// Stores the new balance of `account_id`, recording the previous one in
+    /// the current snapshot first
+    fn set_balance(&mut self, account_id: &Address, balance: u128) {
+        let old_balance = self.balance_of(account_id).unwrap_or_default();
+        let snapshot_id = self.snapshot_id;
+        if snapshot_id > 0 {
+            match self.account_snapshots.get_mut(account_id) {
+                Some(snapshots) => snapshots.update(snapshot_id, old_balance),
+                None => {
+                    let mut snapshots = Snapshots::default();
+                    snapshots.update(snapshot_id, old_balance);
+                    self.account_snapshots
+                        .insert(account_id.clone(), snapshots);
+                }
+            }
+        }
+
+        self.balances.insert(account_id.clone(), balance);
+        self.update_holders(account_id, old_balance, balance);
+    }
+
+    /// Holders from before the index existed (see `state::v0_to_v1`) are
+    /// added on their next balance change or by `ft_index_holders`, which
+    /// takes their `old_balance` off the unindexed supply.
+    fn update_holders(
+        &mut self,
+        account_id: &Address,
+        old_balance: u128,
+        balance: u128,
+    ) {
+        // The escrow belongs to nobody, it never joins the index
+        let is_escrow = Self::is_escrow(account_id);
+        let balance = if is_escrow { 0 } else { balance };
+
+        let is_indexed = self.holder_index.contains_key(account_id);
+        if !is_indexed && !is_escrow {
+            self.unindexed_supply = self
+                .unindexed_supply
+                .checked_sub(old_balance)
+                .expect("The unindexed supply is underflowed");
+        }
+
+        if balance > 0 && !is_indexed {
+            self.holders.push(*account_id);
+            self.holder_index.insert(*account_id, self.holders.len() - 1);
+        } else if balance == 0 && is_indexed {
+            let idx = *self
+                .holder_index
+                .get(account_id)
+                .expect("Can't find the index of the holder");
+            self.holder_index.remove(*account_id);
+            // The last holder takes the place of the removed one
+            self.holders.swap_remove(idx);
+            if let Some(moved_holder) = self.holders.get(idx).copied() {
+                self.holder_index.insert(moved_holder, idx);
+            }
+        }
+    }
+
+    /// Stores the new total supply, recording the previous one in the
+    /// current snapshot first
+    fn set_total_supply(&mut self, total_supply: u128) {
+        let snapshot_id = self.snapshot_id;
+        if snapshot_id > 0 {
+            self.total_supply_snapshots.update(snapshot_id, self.total_supply);
+        }
+
+        self.total_supply = total_supply;
+    }
+
+    fn delegate(&mut self, delegator: &Address, delegatee: Address) {
+        assert!(
+            !Self::is_escrow(&delegatee),
+            "The contract instance can't be a delegate"
+        );
+
+        let from_delegate = self.delegate_of(delegator);
+        self.delegates.insert(delegator.clone(), delegatee);
+
+        emit_event_experimental(FtEvent::DelegateChanged {
+            delegator: *delegator,
+            from_delegate,
+            to_delegate: delegatee,
+        });
+
+        let balance = self.balance_of(delegator).unwrap_or_default();
+        self.move_voting_power(from_delegate, Some(delegatee), balance);
+    }
+
+    /// The escrow never delegates, so escrowed tokens carry no votes
+    fn delegate_of(&self, account_id: &Address) -> Option<Address> {
+        if Self::is_escrow(account_id) {
+            return None;
+        }
+
+        self.delegates.get(account_id).copied()
+    }
+
+    fn move_voting_power(
+        &mut self,
+        from: Option<Address>,
+        to: Option<Address>,
+        amount: u128,
+    ) {
+        if from == to || amount == 0 {
+            return;
+        }
+
+        if let Some(from) = from {
+            self.write_votes(&from, |votes| {
+                votes.checked_sub(amount).expect("Votes are underflowed")
+            });
+        }
+        if let Some(to) = to {
+            self.write_votes(&to, |votes| {
+                votes.checked_add(amount).expect("Votes are overflowed")
+            });
+        }
+    }
+
+    fn write_votes(
+        &mut self,
+        delegate: &Address,
+        update: impl FnOnce(u128) -> u128,
+    ) {
+        let block_number = l1x_sdk::block_number();
+
+        let previous_votes = match self.vote_checkpoints.get(delegate) {
+            Some(checkpoints) => checkpoints.latest(),
+            None => 0,
+        };
+        let new_votes = update(previous_votes);
+
+        match self.vote_checkpoints.get_mut(delegate) {
+            Some(checkpoints) => checkpoints.push(block_number, new_votes),
+            None => {
+                let mut checkpoints = Checkpoints::default();
+                checkpoints.push(block_number, new_votes);
+                self.vote_checkpoints.insert(delegate.clone(), checkpoints);
+            }
+        }
+
+        emit_event_experimental(FtEvent::DelegateVotesChanged {
+            delegate: *delegate,
+            previous_votes: previous_votes.into(),
+            new_votes: new_votes.into(),
+        });
+    }
+
+    fn vesting_schedule(&self, id: u64) -> &VestingSchedule {
+        self.vesting_schedules
+            .get(&id)
+            .unwrap_or_else(|| panic!("Nonexistent vesting schedule {}", id))
+    }
+
+    fn assert_valid_snapshot(&self, snapshot_id: u64) {
+        assert!(
+            snapshot_id > 0 && snapshot_id <= self.snapshot_id,
+            "Nonexistent snapshot id {}",
+            snapshot_id
+        );
     }
 
     fn balance_of(&self, account_id: &Address) -> Option<u128> {
         self.balances.get(account_id).copied()
     }
 
+    /// The contract instance holds the vesting escrow and the tokens locked
+    /// by the bridge, which are accounted for by the schedules and
+    /// `bridge.locked`
+    fn is_escrow(account_id: &Address) -> bool {
+        *account_id == contract_instance_address()
+    }
+
+    fn assert_not_escrow(&self, recipient_id: &Address) {
+        assert!(
+            !Self::is_escrow(recipient_id),
+            "Tokens can't be sent to the contract instance"
+        );
+    }
+
+    /// Recounts the supply of the accounts missing from the holder index
+    /// after a migration. Layout 0 had no index, so that's all the supply
+    /// but the escrow.
+    fn count_unindexed_supply(&mut self) {
+        let escrow_id = contract_instance_address();
+        self.update_holders(&escrow_id, 0, 0);
+
+        let indexed_supply: u128 = (0..self.holders.len())
+            .map(|idx| {
+                let account_id =
+                    *self.holders.get(idx).expect("Can't get a holder");
+                self.balance_of(&account_id).unwrap_or_default()
+            })
+            .sum();
+        let escrowed = self.balance_of(&escrow_id).unwrap_or_default();
+        self.unindexed_supply = self.total_supply - indexed_supply - escrowed;
+    }
+
+    fn assert_holders_indexed(&self) {
+        assert!(
+            self.unindexed_supply == 0,
+            "{} tokens are held by accounts missing from the holder index, see ft_index_holders",
+            self.unindexed_supply
+        );
+    }
+
+    fn assert_not_paused(&self) {
+        assert!(!self.paused, "The contract is paused");
+    }
+
+    fn assert_within_max_supply(&self, total_supply: u128) {
+        if let Some(max_supply) = &self.metadata.max_supply {
+            assert!(
+                total_supply <= max_supply.0,
+                "total_supply would exceed max_supply {}",
+                max_supply.0
+            );
+        }
+    }
+
     fn assert_if_no_balance(&self, account_id: &Address) {
         assert_ne!(
             self.balances.get(account_id),
@@ -293,13 +1857,21 @@ impl L1xFtErc20 {
     }
 
     fn load() -> Self {
-        match l1x_sdk::storage_read(STORAGE_CONTRACT_KEY) {
-            Some(bytes) => Self::try_from_slice(&bytes).unwrap(),
+        match state::read(STORAGE_CONTRACT_KEY) {
+            Some((version, payload)) => {
+                state::assert_not_newer(version);
+                assert!(
+                    version == STATE_VERSION,
+                    "The stored state version {} is outdated, the owner must call migrate",
+                    version
+                );
+                Self::try_from_slice(&payload).unwrap()
+            }
             None => panic!("The contract isn't initialized"),
         }
     }
 
     fn save(&mut self) {
-        l1x_sdk::storage_write(STORAGE_CONTRACT_KEY, &self.try_to_vec().unwrap());
+        state::write(STORAGE_CONTRACT_KEY, &self.try_to_vec().unwrap());
     }
 }
@@ -0,0 +1,37 @@
+//! Balance and total supply snapshots.
+//!
+//! `ft_snapshot` only bumps the current snapshot id. Values are written
+//! lazily: right before a balance or the total supply changes for the first
+//! time after a snapshot, its previous value is recorded against the current
+//! id. A snapshot with no recorded value for an account means the balance
+//! hasn't changed since, so the current balance is the historical one.
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Default, Clone)]
+pub(crate) struct Snapshots {
+    ids: Vec<u64>,
+    values: Vec<u128>,
+}
+
+impl Snapshots {
+    /// Records `value` as the value at `snapshot_id` unless a value has
+    /// already been recorded for it.
+    pub fn update(&mut self, snapshot_id: u64, value: u128) {
+        if snapshot_id == 0 {
+            return;
+        }
+
+        let last_id = self.ids.last().copied().unwrap_or_default();
+        if last_id < snapshot_id {
+            self.ids.push(snapshot_id);
+            self.values.push(value);
+        }
+    }
+
+    /// Returns the value at `snapshot_id`, or `None` if the value hasn't
+    /// changed since that snapshot was taken.
+    pub fn value_at(&self, snapshot_id: u64) -> Option<u128> {
+        let idx = self.ids.partition_point(|id| *id < snapshot_id);
+        self.values.get(idx).copied()
+    }
+}
@@ -0,0 +1,105 @@
+//! Versioned storage of the contract state.
+//!
+//! The state is stored in the versioned envelope of `l1x-versioned-state`,
+//! where the payload is the Borsh encoding of the layout of that version.
+//! Instances deployed before versioning store the bare payload of layout 0,
+//! which starts with the token name and reads as version 0, see
+//! `l1x_versioned_state`.
+//!
+//! Every change of the layout bumps [`STATE_VERSION`] and adds a step to
+//! [`upgrade`] that converts the payload of the previous version. New fields
+//! are only appended at the end of [`L1xFtErc20`], so a step just appends the
+//! encoding of their initial values.
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{FTMetadata, L1xFtErc20};
+
+pub(crate) use l1x_versioned_state::read;
+
+/// Version of the layout written by this code.
+pub const STATE_VERSION: u16 = 1;
+
+pub(crate) fn write(key: &[u8], payload: &[u8]) {
+    l1x_versioned_state::write(key, STATE_VERSION, payload);
+}
+
+pub(crate) fn assert_not_newer(version: u16) {
+    l1x_versioned_state::assert_not_newer(version, STATE_VERSION);
+}
+
+/// Converts a payload of layout `version` to the current layout, one version
+/// at a time.
+pub(crate) fn upgrade(mut version: u16, mut payload: Vec<u8>) -> L1xFtErc20 {
+    assert_not_newer(version);
+    let from_version = version;
+
+    while version < STATE_VERSION {
+        payload = match version {
+            0 => v0_to_v1(payload),
+            _ => {
+                unreachable!("No migration from the state version {}", version)
+            }
+        };
+        version += 1;
+    }
+
+    let mut contract = L1xFtErc20::try_from_slice(&payload).unwrap();
+    if from_version < 1 {
+        // The holder index of layout 1 starts empty
+        contract.count_unindexed_supply();
+    }
+
+    contract
+}
+
+/// Layout 0: the token before roles, pausing and `max_supply` were added.
+#[derive(BorshDeserialize)]
+struct FTMetadataV0 {
+    name: String,
+    decimals: u8,
+    symbol: String,
+    icon: Option<String>,
+}
+
+/// Layout 1 adds `max_supply` to the metadata and appends the fields from
+/// `roles` on, with the owner granted the roles `new` grants. The balances
+/// and allowances live under the same storage keys. Existing holders join the
+/// holder index through `ft_index_holders` or on their next balance change,
+/// the holder views wait for them until `upgrade` counts their supply.
+fn v0_to_v1(payload: Vec<u8>) -> Vec<u8> {
+    let mut rest = payload.as_slice();
+    let old = FTMetadataV0::deserialize(&mut rest).unwrap();
+    let metadata = FTMetadata {
+        name: old.name,
+        decimals: old.decimals,
+        symbol: old.symbol,
+        icon: old.icon,
+        max_supply: None,
+    };
+
+    let mut initial = L1xFtErc20::with_metadata(metadata);
+    initial.grant_owner_roles();
+
+    [
+        initial.metadata.try_to_vec().unwrap(),
+        rest.to_vec(),
+        initial.roles.try_to_vec().unwrap(),
+        initial.paused.try_to_vec().unwrap(),
+        initial.nonces.try_to_vec().unwrap(),
+        initial.snapshot_id.try_to_vec().unwrap(),
+        initial.snapshot_blocks.try_to_vec().unwrap(),
+        initial.account_snapshots.try_to_vec().unwrap(),
+        initial.total_supply_snapshots.try_to_vec().unwrap(),
+        initial.delegates.try_to_vec().unwrap(),
+        initial.vote_checkpoints.try_to_vec().unwrap(),
+        initial.fee_config.try_to_vec().unwrap(),
+        initial.compliance.try_to_vec().unwrap(),
+        initial.vesting_schedules.try_to_vec().unwrap(),
+        initial.next_vesting_id.try_to_vec().unwrap(),
+        initial.holders.try_to_vec().unwrap(),
+        initial.holder_index.try_to_vec().unwrap(),
+        initial.bridge.try_to_vec().unwrap(),
+        initial.unindexed_supply.try_to_vec().unwrap(),
+    ]
+    .concat()
+}
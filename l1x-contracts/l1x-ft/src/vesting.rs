@@ -0,0 +1,96 @@
+//! Linear vesting schedules with a cliff.
+//!
+//! The tokens of a schedule are held in escrow by the contract instance,
+//! which isn't a holder, carries no votes and can't be sent tokens
+//! otherwise.
+//! Nothing vests before `start + cliff`, then the vested amount grows
+//! linearly from `start` and reaches `total` at `start + duration`. Revoking
+//! a schedule freezes it at the amount vested so far, the beneficiary can
+//! still release that part.
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk::types::{Address, U128, U64};
+use serde::Serialize;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Clone)]
+pub struct VestingSchedule {
+    pub beneficiary: Address,
+    /// Account that funded the schedule and gets unvested tokens back on
+    /// revocation
+    pub funder: Address,
+    pub total: U128,
+    pub released: U128,
+    /// Timestamps and durations are in `l1x_sdk::block_timestamp` units
+    pub start: U64,
+    pub cliff: U64,
+    pub duration: U64,
+    pub revocable: bool,
+    pub revoked: bool,
+}
+
+impl VestingSchedule {
+    pub fn new(
+        beneficiary: Address,
+        funder: Address,
+        total: u128,
+        start: u64,
+        cliff: u64,
+        duration: u64,
+        revocable: bool,
+    ) -> Self {
+        assert!(total > 0, "Vesting total must be positive");
+        assert!(duration > 0, "Vesting duration must be positive");
+        assert!(cliff <= duration, "Vesting cliff can't exceed the duration");
+
+        Self {
+            beneficiary,
+            funder,
+            total: total.into(),
+            released: 0.into(),
+            start: start.into(),
+            cliff: cliff.into(),
+            duration: duration.into(),
+            revocable,
+            revoked: false,
+        }
+    }
+
+    /// Returns the amount vested at `timestamp`, released or not.
+    pub fn vested_amount(&self, timestamp: u64) -> u128 {
+        let (total, start, duration) =
+            (self.total.0, self.start.0, self.duration.0);
+
+        if self.revoked {
+            return total;
+        }
+        if timestamp < start.saturating_add(self.cliff.0) {
+            return 0;
+        }
+
+        let elapsed = timestamp - start;
+        if elapsed >= duration {
+            return total;
+        }
+
+        // total * elapsed / duration without overflowing
+        let (elapsed, duration) = (u128::from(elapsed), u128::from(duration));
+        total / duration * elapsed + total % duration * elapsed / duration
+    }
+
+    pub fn releasable_amount(&self, timestamp: u64) -> u128 {
+        self.vested_amount(timestamp) - self.released.0
+    }
+
+    /// Freezes the schedule at the amount vested at `timestamp` and returns
+    /// the unvested amount.
+    pub fn revoke(&mut self, timestamp: u64) -> u128 {
+        assert!(self.revocable, "The vesting schedule isn't revocable");
+        assert!(!self.revoked, "The vesting schedule is already revoked");
+
+        let vested_amount = self.vested_amount(timestamp);
+        let unvested_amount = self.total.0 - vested_amount;
+        self.total = vested_amount.into();
+        self.revoked = true;
+
+        unvested_amount
+    }
+}
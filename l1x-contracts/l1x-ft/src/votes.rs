@@ -0,0 +1,40 @@
+//! Voting power checkpoints (ERC20Votes).
+//!
+//! Every delegate keeps a list of `(block_number, votes)` checkpoints sorted
+//! by block number. Several changes in the same block overwrite the last
+//! checkpoint, so there is at most one checkpoint per block.
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Default, Clone)]
+pub(crate) struct Checkpoints {
+    blocks: Vec<u64>,
+    votes: Vec<u128>,
+}
+
+impl Checkpoints {
+    pub fn latest(&self) -> u128 {
+        self.votes.last().copied().unwrap_or_default()
+    }
+
+    pub fn push(&mut self, block_number: u64, votes: u128) {
+        match self.blocks.last() {
+            Some(last_block) if *last_block == block_number => {
+                *self.votes.last_mut().expect("Checkpoint without votes") =
+                    votes;
+            }
+            _ => {
+                self.blocks.push(block_number);
+                self.votes.push(votes);
+            }
+        }
+    }
+
+    /// Returns the votes at the end of `block_number`.
+    pub fn votes_at(&self, block_number: u64) -> u128 {
+        let idx = self.blocks.partition_point(|block| *block <= block_number);
+        match idx {
+            0 => 0,
+            _ => self.votes[idx - 1],
+        }
+    }
+}
@@ -0,0 +1,2135 @@
+use std::collections::BTreeMap;
+
+use base64::Engine;
+use borsh::BorshSerialize;
+use ethers::abi::{encode, Token};
+use ethers::signers::LocalWallet;
+use ethers::types::{Log, H256, U256 as EthU256};
+use ethers::utils::keccak256;
+use l1x_ft::{
+    BridgeMode, ComplianceMode, FTMetadata, FtEvent, L1xFtErc20, Role,
+    TransferRestriction, L1X_CHAIN_ID, STATE_VERSION,
+};
+use l1x_mock_host as host;
+use l1x_sdk::store::LookupMap;
+use l1x_sdk::types::{Address, U128, U64};
+
+fn metadata() -> FTMetadata {
+    serde_json::from_value(serde_json::json!({
+        "name": "L1X Token",
+        "decimals": 18,
+        "symbol": "LTK",
+        "icon": null,
+    }))
+    .unwrap()
+}
+
+fn capped_metadata(max_supply: u128) -> FTMetadata {
+    serde_json::from_value(serde_json::json!({
+        "name": "L1X Token",
+        "decimals": 18,
+        "symbol": "LTK",
+        "icon": null,
+        "max_supply": max_supply.to_string(),
+    }))
+    .unwrap()
+}
+
+fn alice() -> Address {
+    host::address(1)
+}
+
+fn bob() -> Address {
+    host::address(2)
+}
+
+fn carol() -> Address {
+    host::address(3)
+}
+
+fn owner() -> Address {
+    host::contract_owner()
+}
+
+/// Deploys the token with 1000 tokens for alice and 500 for bob.
+fn setup() {
+    host::set_caller(owner());
+    L1xFtErc20::new(
+        metadata(),
+        vec![alice(), bob()],
+        vec![U128(1000), U128(500)],
+    );
+}
+
+fn balance(account: Address) -> u128 {
+    L1xFtErc20::ft_balance_of(account).0
+}
+
+#[test]
+fn new_initializes_balances_and_supply() {
+    setup();
+
+    assert_eq!(balance(alice()), 1000);
+    assert_eq!(balance(bob()), 500);
+    assert_eq!(balance(carol()), 0);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1500);
+}
+
+#[test]
+#[should_panic(expected = "Only the owner can call this function")]
+fn new_rejects_non_owner() {
+    host::set_caller(alice());
+    L1xFtErc20::new(metadata(), vec![], vec![]);
+}
+
+#[test]
+#[should_panic(expected = "account_ids and amounts length mismatch")]
+fn new_rejects_length_mismatch() {
+    L1xFtErc20::new(metadata(), vec![alice()], vec![]);
+}
+
+#[test]
+#[should_panic(expected = "The contract isn't initialized")]
+fn views_require_initialization() {
+    L1xFtErc20::ft_total_supply();
+}
+
+#[test]
+fn metadata_views() {
+    setup();
+
+    assert_eq!(L1xFtErc20::ft_name(), "L1X Token");
+    assert_eq!(L1xFtErc20::ft_symbol(), "LTK");
+    assert_eq!(L1xFtErc20::ft_decimals(), 18);
+}
+
+#[test]
+fn owner_can_mint() {
+    setup();
+
+    L1xFtErc20::ft_mint(carol(), U128(250));
+
+    assert_eq!(balance(carol()), 250);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1750);
+    assert!(host::logs().iter().any(|log| log.starts_with("Minted 250")));
+}
+
+#[test]
+#[should_panic(expected = "is missing role Minter")]
+fn non_minter_cannot_mint() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_mint(alice(), U128(1));
+}
+
+#[test]
+fn transfer_moves_balance() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(carol(), U128(300));
+
+    assert_eq!(balance(alice()), 700);
+    assert_eq!(balance(carol()), 300);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1500);
+}
+
+#[test]
+#[should_panic(expected = "Not enough balance to transfer")]
+fn transfer_rejects_overdraft() {
+    setup();
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer(carol(), U128(501));
+}
+
+#[test]
+fn approve_and_adjust_allowance() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(bob(), U128(100));
+    assert_eq!(L1xFtErc20::ft_allowance(alice(), bob()).0, 100);
+
+    L1xFtErc20::ft_increase_allowance(bob(), U128(50));
+    assert_eq!(L1xFtErc20::ft_allowance(alice(), bob()).0, 150);
+
+    L1xFtErc20::ft_decrease_allowance(bob(), U128(20));
+    assert_eq!(L1xFtErc20::ft_allowance(alice(), bob()).0, 130);
+
+    assert_eq!(L1xFtErc20::ft_allowance(bob(), alice()).0, 0);
+}
+
+#[test]
+#[should_panic(expected = "should have token on the balance")]
+fn approve_requires_balance() {
+    setup();
+
+    host::set_caller(carol());
+    L1xFtErc20::ft_approve(bob(), U128(100));
+}
+
+#[test]
+fn transfer_from_spends_allowance() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(bob(), U128(100));
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer_from(alice(), carol(), U128(60));
+
+    assert_eq!(balance(alice()), 940);
+    assert_eq!(balance(carol()), 60);
+    assert_eq!(L1xFtErc20::ft_allowance(alice(), bob()).0, 40);
+}
+
+#[test]
+fn transfer_from_can_spend_the_whole_allowance() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(bob(), U128(100));
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer_from(alice(), carol(), U128(100));
+
+    assert_eq!(balance(carol()), 100);
+    assert_eq!(L1xFtErc20::ft_allowance(alice(), bob()).0, 0);
+}
+
+#[test]
+#[should_panic(expected = "didn't set allowance")]
+fn transfer_from_requires_allowance() {
+    setup();
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer_from(alice(), carol(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "The allowance is too small")]
+fn transfer_from_rejects_exceeding_allowance() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(bob(), U128(10));
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer_from(alice(), carol(), U128(11));
+}
+
+#[test]
+fn new_emits_mint_per_holder() {
+    setup();
+
+    let owner = host::contract_owner();
+    let grant =
+        |role| FtEvent::RoleGranted { role, account: owner, sender: owner };
+    assert_eq!(
+        host::decoded_events::<FtEvent>(),
+        vec![
+            grant(Role::Admin),
+            grant(Role::Minter),
+            grant(Role::Pauser),
+            FtEvent::Mint { to: alice(), amount: U128(1000) },
+            FtEvent::Mint { to: bob(), amount: U128(500) },
+        ]
+    );
+}
+
+#[test]
+fn transfer_and_mint_emit_events() {
+    setup();
+    host::clear_captures();
+
+    L1xFtErc20::ft_mint(carol(), U128(5));
+    host::set_caller(carol());
+    L1xFtErc20::ft_transfer(alice(), U128(2));
+
+    assert_eq!(
+        host::decoded_events::<FtEvent>(),
+        vec![
+            FtEvent::Mint { to: carol(), amount: U128(5) },
+            FtEvent::Transfer { from: carol(), to: alice(), amount: U128(2) },
+        ]
+    );
+}
+
+#[test]
+fn allowance_changes_emit_approval() {
+    setup();
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(bob(), U128(100));
+    host::clear_captures();
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer_from(alice(), carol(), U128(30));
+
+    assert_eq!(
+        host::decoded_events::<FtEvent>(),
+        vec![
+            FtEvent::Approval {
+                owner: alice(),
+                spender: bob(),
+                old: U128(100),
+                new: U128(70),
+            },
+            FtEvent::Transfer { from: alice(), to: carol(), amount: U128(30) },
+        ]
+    );
+}
+
+#[test]
+fn burn_destroys_caller_tokens() {
+    setup();
+    host::clear_captures();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_burn(U128(400));
+
+    assert_eq!(balance(alice()), 600);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1100);
+    assert_eq!(
+        host::decoded_events::<FtEvent>(),
+        vec![FtEvent::Burn { from: alice(), amount: U128(400) }]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Not enough balance to burn")]
+fn burn_rejects_overdraft() {
+    setup();
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_burn(U128(501));
+}
+
+#[test]
+fn burn_from_spends_allowance() {
+    setup();
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(bob(), U128(100));
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_burn_from(alice(), U128(40));
+
+    assert_eq!(balance(alice()), 960);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1460);
+    assert_eq!(L1xFtErc20::ft_allowance(alice(), bob()).0, 60);
+}
+
+#[test]
+fn burn_from_can_burn_the_whole_allowance() {
+    setup();
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(bob(), U128(100));
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_burn_from(alice(), U128(100));
+
+    assert_eq!(balance(alice()), 900);
+    assert_eq!(L1xFtErc20::ft_allowance(alice(), bob()).0, 0);
+}
+
+#[test]
+#[should_panic(expected = "didn't set allowance")]
+fn burn_from_requires_allowance() {
+    setup();
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_burn_from(alice(), U128(1));
+}
+
+#[test]
+fn max_supply_caps_mint() {
+    L1xFtErc20::new(capped_metadata(2000), vec![alice()], vec![U128(1500)]);
+
+    L1xFtErc20::ft_mint(bob(), U128(500));
+
+    assert_eq!(L1xFtErc20::ft_max_supply(), Some(U128(2000)));
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 2000);
+}
+
+#[test]
+#[should_panic(expected = "total_supply would exceed max_supply 2000")]
+fn mint_rejects_exceeding_max_supply() {
+    L1xFtErc20::new(capped_metadata(2000), vec![alice()], vec![U128(1500)]);
+
+    L1xFtErc20::ft_mint(bob(), U128(501));
+}
+
+#[test]
+#[should_panic(expected = "total_supply would exceed max_supply 100")]
+fn new_rejects_holders_above_max_supply() {
+    L1xFtErc20::new(capped_metadata(100), vec![alice()], vec![U128(101)]);
+}
+
+#[test]
+fn burned_tokens_free_up_capped_supply() {
+    L1xFtErc20::new(capped_metadata(100), vec![alice()], vec![U128(100)]);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_burn(U128(30));
+    host::set_caller(owner());
+    L1xFtErc20::ft_mint(bob(), U128(30));
+
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 100);
+}
+
+#[test]
+fn owner_starts_as_admin_and_minter() {
+    setup();
+
+    assert!(L1xFtErc20::ft_has_role(Role::Admin, owner()));
+    assert!(L1xFtErc20::ft_has_role(Role::Minter, owner()));
+    assert!(!L1xFtErc20::ft_has_role(Role::Minter, alice()));
+}
+
+#[test]
+fn granted_minter_can_mint() {
+    setup();
+    L1xFtErc20::ft_grant_role(Role::Minter, alice());
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_mint(carol(), U128(10));
+
+    assert_eq!(balance(carol()), 10);
+}
+
+#[test]
+#[should_panic(expected = "is missing role Minter")]
+fn revoked_minter_cannot_mint() {
+    setup();
+    L1xFtErc20::ft_grant_role(Role::Minter, alice());
+    L1xFtErc20::ft_revoke_role(Role::Minter, alice());
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_mint(carol(), U128(10));
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_grant_roles() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_grant_role(Role::Minter, alice());
+}
+
+#[test]
+fn role_changes_emit_events() {
+    setup();
+    host::clear_captures();
+
+    L1xFtErc20::ft_grant_role(Role::Minter, alice());
+    L1xFtErc20::ft_grant_role(Role::Minter, alice());
+    L1xFtErc20::ft_revoke_role(Role::Minter, alice());
+    L1xFtErc20::ft_renounce_role(Role::Pauser);
+
+    assert_eq!(
+        host::decoded_events::<FtEvent>(),
+        vec![
+            FtEvent::RoleGranted {
+                role: Role::Minter,
+                account: alice(),
+                sender: owner(),
+            },
+            FtEvent::RoleRevoked {
+                role: Role::Minter,
+                account: alice(),
+                sender: owner(),
+            },
+            FtEvent::RoleRevoked {
+                role: Role::Pauser,
+                account: owner(),
+                sender: owner(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn admin_can_hand_over_and_renounce() {
+    setup();
+    L1xFtErc20::ft_grant_role(Role::Admin, alice());
+    L1xFtErc20::ft_renounce_role(Role::Admin);
+
+    assert!(!L1xFtErc20::ft_has_role(Role::Admin, owner()));
+    assert!(L1xFtErc20::ft_has_role(Role::Admin, alice()));
+}
+
+#[test]
+fn pause_blocks_transfers_until_unpaused() {
+    setup();
+    L1xFtErc20::ft_pause();
+    assert!(L1xFtErc20::ft_is_paused());
+
+    L1xFtErc20::ft_unpause();
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(bob(), U128(1));
+
+    assert!(!L1xFtErc20::ft_is_paused());
+    assert_eq!(balance(bob()), 501);
+}
+
+#[test]
+fn pause_and_unpause_emit_events() {
+    setup();
+    host::clear_captures();
+
+    L1xFtErc20::ft_pause();
+    L1xFtErc20::ft_unpause();
+
+    assert_eq!(
+        host::decoded_events::<FtEvent>(),
+        vec![
+            FtEvent::Paused { account: owner() },
+            FtEvent::Unpaused { account: owner() },
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "The contract is paused")]
+fn paused_contract_rejects_transfer() {
+    setup();
+    L1xFtErc20::ft_pause();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(bob(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "The contract is paused")]
+fn paused_contract_rejects_transfer_from() {
+    setup();
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(bob(), U128(10));
+    host::set_caller(owner());
+    L1xFtErc20::ft_pause();
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer_from(alice(), carol(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "The contract is paused")]
+fn paused_contract_rejects_mint() {
+    setup();
+    L1xFtErc20::ft_pause();
+
+    L1xFtErc20::ft_mint(carol(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "is missing role Pauser")]
+fn non_pauser_cannot_pause() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_pause();
+}
+
+#[test]
+fn granted_pauser_can_pause() {
+    setup();
+    L1xFtErc20::ft_grant_role(Role::Pauser, alice());
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_pause();
+
+    assert!(L1xFtErc20::ft_is_paused());
+}
+
+fn permit_signer() -> (LocalWallet, Address) {
+    let wallet = LocalWallet::from_bytes(&[0x11; 32]).unwrap();
+    let address = Address::from(wallet.address().0);
+    (wallet, address)
+}
+
+fn sign_permit(
+    wallet: &LocalWallet,
+    owner_id: Address,
+    amount: u128,
+    deadline: u64,
+    nonce: u64,
+) -> String {
+    let digest = L1xFtErc20::ft_permit_digest(
+        owner_id,
+        bob(),
+        U128(amount),
+        U64(deadline),
+        U64(nonce),
+    );
+    let digest = H256::from_slice(&hex::decode(digest).unwrap());
+    wallet.sign_hash(digest).unwrap().to_string()
+}
+
+#[test]
+fn permit_sets_allowance_and_bumps_nonce() {
+    let (wallet, signer) = permit_signer();
+    L1xFtErc20::new(metadata(), vec![signer], vec![U128(100)]);
+    host::set_block(10, 1_000);
+    let signature = sign_permit(&wallet, signer, 40, 2_000, 0);
+
+    host::set_caller(carol());
+    L1xFtErc20::ft_permit(
+        signer,
+        bob(),
+        U128(40),
+        U64(2_000),
+        U64(0),
+        signature,
+    );
+
+    assert_eq!(L1xFtErc20::ft_allowance(signer, bob()).0, 40);
+    assert_eq!(L1xFtErc20::ft_nonces(signer).0, 1);
+}
+
+#[test]
+#[should_panic(expected = "Invalid permit nonce")]
+fn permit_cannot_be_replayed() {
+    let (wallet, signer) = permit_signer();
+    L1xFtErc20::new(metadata(), vec![signer], vec![U128(100)]);
+    let signature = sign_permit(&wallet, signer, 40, 2_000, 0);
+
+    L1xFtErc20::ft_permit(
+        signer,
+        bob(),
+        U128(40),
+        U64(2_000),
+        U64(0),
+        signature.clone(),
+    );
+    L1xFtErc20::ft_permit(
+        signer,
+        bob(),
+        U128(40),
+        U64(2_000),
+        U64(0),
+        signature,
+    );
+}
+
+#[test]
+#[should_panic(expected = "The permit has expired")]
+fn permit_rejects_expired_deadline() {
+    let (wallet, signer) = permit_signer();
+    L1xFtErc20::new(metadata(), vec![signer], vec![U128(100)]);
+    let signature = sign_permit(&wallet, signer, 40, 2_000, 0);
+
+    host::set_block(10, 2_001);
+    L1xFtErc20::ft_permit(
+        signer,
+        bob(),
+        U128(40),
+        U64(2_000),
+        U64(0),
+        signature,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid permit signature")]
+fn permit_rejects_tampered_amount() {
+    let (wallet, signer) = permit_signer();
+    L1xFtErc20::new(metadata(), vec![signer], vec![U128(100)]);
+    let signature = sign_permit(&wallet, signer, 40, 2_000, 0);
+
+    L1xFtErc20::ft_permit(
+        signer,
+        bob(),
+        U128(90),
+        U64(2_000),
+        U64(0),
+        signature,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid permit signature")]
+fn permit_is_bound_to_the_contract_instance() {
+    let (wallet, signer) = permit_signer();
+    L1xFtErc20::new(metadata(), vec![signer], vec![U128(100)]);
+    let signature = sign_permit(&wallet, signer, 40, 2_000, 0);
+
+    host::set_contract_instance(host::address(0xcc));
+    L1xFtErc20::ft_permit(
+        signer,
+        bob(),
+        U128(40),
+        U64(2_000),
+        U64(0),
+        signature,
+    );
+}
+
+#[test]
+fn batch_transfer_moves_every_leg() {
+    setup();
+    host::clear_captures();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_batch_transfer(
+        vec![bob(), carol(), bob()],
+        vec![U128(100), U128(200), U128(50)],
+    );
+
+    assert_eq!(balance(alice()), 650);
+    assert_eq!(balance(bob()), 650);
+    assert_eq!(balance(carol()), 200);
+    assert_eq!(
+        host::decoded_events::<FtEvent>(),
+        vec![
+            FtEvent::Transfer { from: alice(), to: bob(), amount: U128(100) },
+            FtEvent::Transfer { from: alice(), to: carol(), amount: U128(200) },
+            FtEvent::Transfer { from: alice(), to: bob(), amount: U128(50) },
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "recipient_ids and amounts length mismatch")]
+fn batch_transfer_rejects_length_mismatch() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_batch_transfer(vec![bob(), carol()], vec![U128(1)]);
+}
+
+#[test]
+#[should_panic(expected = "Not enough balance to transfer")]
+fn batch_transfer_checks_the_total_upfront() {
+    setup();
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_batch_transfer(
+        vec![alice(), carol()],
+        vec![U128(300), U128(201)],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Batch total is overflowed")]
+fn batch_transfer_rejects_overflowing_total() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_batch_transfer(
+        vec![bob(), carol()],
+        vec![U128(u128::MAX), U128(1)],
+    );
+}
+
+#[test]
+fn airdrop_mint_credits_every_recipient() {
+    setup();
+
+    host::clear_captures();
+
+    L1xFtErc20::ft_airdrop_mint(vec![carol(), bob()], vec![U128(10), U128(20)]);
+
+    assert_eq!(balance(carol()), 10);
+    assert_eq!(balance(bob()), 520);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1530);
+    let zero = Address::from([0u8; 20]);
+    assert_eq!(
+        host::decoded_events::<FtEvent>(),
+        vec![
+            FtEvent::Transfer { from: zero, to: carol(), amount: U128(10) },
+            FtEvent::Transfer { from: zero, to: bob(), amount: U128(20) },
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "is missing role Minter")]
+fn airdrop_mint_requires_minter() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_airdrop_mint(vec![alice()], vec![U128(10)]);
+}
+
+#[test]
+#[should_panic(expected = "total_supply would exceed max_supply 1000")]
+fn airdrop_mint_respects_max_supply() {
+    L1xFtErc20::new(capped_metadata(1000), vec![alice()], vec![U128(900)]);
+
+    L1xFtErc20::ft_airdrop_mint(vec![bob(), carol()], vec![U128(50), U128(51)]);
+}
+
+#[test]
+fn transfer_to_self_keeps_balance() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(alice(), U128(100));
+
+    assert_eq!(balance(alice()), 1000);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1500);
+}
+
+#[test]
+fn snapshot_freezes_balances_and_supply() {
+    setup();
+    host::set_block(7, 100);
+    let first = L1xFtErc20::ft_snapshot();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(carol(), U128(300));
+    L1xFtErc20::ft_burn(U128(100));
+
+    assert_eq!(first.0, 1);
+    assert_eq!(L1xFtErc20::ft_snapshot_block(first).0, 7);
+    assert_eq!(L1xFtErc20::ft_balance_of_at(alice(), first).0, 1000);
+    assert_eq!(L1xFtErc20::ft_balance_of_at(carol(), first).0, 0);
+    assert_eq!(L1xFtErc20::ft_balance_of_at(bob(), first).0, 500);
+    assert_eq!(L1xFtErc20::ft_total_supply_at(first).0, 1500);
+
+    assert_eq!(balance(alice()), 600);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1400);
+}
+
+#[test]
+fn later_snapshots_see_later_changes() {
+    setup();
+    let first = L1xFtErc20::ft_snapshot();
+    L1xFtErc20::ft_mint(carol(), U128(50));
+    let second = L1xFtErc20::ft_snapshot();
+    L1xFtErc20::ft_mint(carol(), U128(25));
+
+    assert_eq!(L1xFtErc20::ft_balance_of_at(carol(), first).0, 0);
+    assert_eq!(L1xFtErc20::ft_balance_of_at(carol(), second).0, 50);
+    assert_eq!(balance(carol()), 75);
+    assert_eq!(L1xFtErc20::ft_total_supply_at(first).0, 1500);
+    assert_eq!(L1xFtErc20::ft_total_supply_at(second).0, 1550);
+}
+
+#[test]
+fn supply_changes_before_the_first_snapshot_are_current() {
+    setup();
+    L1xFtErc20::ft_mint(carol(), U128(50));
+    let first = L1xFtErc20::ft_snapshot();
+    L1xFtErc20::ft_mint(carol(), U128(25));
+
+    assert_eq!(L1xFtErc20::ft_total_supply_at(first).0, 1550);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1575);
+}
+
+#[test]
+#[should_panic(expected = "Nonexistent snapshot id 1")]
+fn balance_of_at_rejects_future_snapshots() {
+    setup();
+
+    L1xFtErc20::ft_balance_of_at(alice(), U64(1));
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn snapshot_requires_admin() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_snapshot();
+}
+
+#[test]
+fn delegation_moves_voting_power() {
+    setup();
+    host::set_block(10, 1_000);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_delegate(alice());
+    host::set_caller(bob());
+    L1xFtErc20::ft_delegate(alice());
+
+    assert_eq!(L1xFtErc20::ft_delegates(bob()), Some(alice()));
+    assert_eq!(L1xFtErc20::ft_get_votes(alice()).0, 1500);
+    assert_eq!(L1xFtErc20::ft_get_votes(bob()).0, 0);
+}
+
+#[test]
+fn transfers_mints_and_burns_move_votes() {
+    setup();
+    host::set_block(10, 1_000);
+    host::set_caller(alice());
+    L1xFtErc20::ft_delegate(alice());
+    host::set_caller(carol());
+    L1xFtErc20::ft_delegate(bob());
+
+    host::set_block(11, 1_010);
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(carol(), U128(300));
+    L1xFtErc20::ft_burn(U128(100));
+    host::set_caller(owner());
+    L1xFtErc20::ft_mint(carol(), U128(50));
+
+    assert_eq!(L1xFtErc20::ft_get_votes(alice()).0, 600);
+    assert_eq!(L1xFtErc20::ft_get_votes(bob()).0, 350);
+}
+
+#[test]
+fn past_votes_follow_checkpoints() {
+    setup();
+    host::set_block(10, 1_000);
+    host::set_caller(alice());
+    L1xFtErc20::ft_delegate(alice());
+
+    host::set_block(20, 2_000);
+    L1xFtErc20::ft_transfer(bob(), U128(400));
+
+    host::set_block(30, 3_000);
+    assert_eq!(L1xFtErc20::ft_get_past_votes(alice(), U64(9)).0, 0);
+    assert_eq!(L1xFtErc20::ft_get_past_votes(alice(), U64(10)).0, 1000);
+    assert_eq!(L1xFtErc20::ft_get_past_votes(alice(), U64(19)).0, 1000);
+    assert_eq!(L1xFtErc20::ft_get_past_votes(alice(), U64(20)).0, 600);
+}
+
+#[test]
+#[should_panic(expected = "Block 30 is not yet mined")]
+fn past_votes_reject_current_block() {
+    setup();
+    host::set_block(30, 3_000);
+
+    L1xFtErc20::ft_get_past_votes(alice(), U64(30));
+}
+
+#[test]
+fn delegate_emits_events() {
+    setup();
+    host::clear_captures();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_delegate(bob());
+
+    assert_eq!(
+        host::decoded_events::<FtEvent>(),
+        vec![
+            FtEvent::DelegateChanged {
+                delegator: alice(),
+                from_delegate: None,
+                to_delegate: bob(),
+            },
+            FtEvent::DelegateVotesChanged {
+                delegate: bob(),
+                previous_votes: U128(0),
+                new_votes: U128(1000),
+            },
+        ]
+    );
+}
+
+fn sign_delegation(
+    wallet: &LocalWallet,
+    delegatee: Address,
+    nonce: u64,
+    expiry: u64,
+) -> String {
+    let digest =
+        L1xFtErc20::ft_delegation_digest(delegatee, U64(nonce), U64(expiry));
+    let digest = H256::from_slice(&hex::decode(digest).unwrap());
+    wallet.sign_hash(digest).unwrap().to_string()
+}
+
+#[test]
+fn delegate_by_sig_uses_the_signer() {
+    let (wallet, signer) = permit_signer();
+    L1xFtErc20::new(metadata(), vec![signer], vec![U128(100)]);
+    let signature = sign_delegation(&wallet, bob(), 0, 2_000);
+
+    host::set_caller(carol());
+    L1xFtErc20::ft_delegate_by_sig(bob(), U64(0), U64(2_000), signature);
+
+    assert_eq!(L1xFtErc20::ft_delegates(signer), Some(bob()));
+    assert_eq!(L1xFtErc20::ft_get_votes(bob()).0, 100);
+    assert_eq!(L1xFtErc20::ft_nonces(signer).0, 1);
+}
+
+#[test]
+#[should_panic(expected = "Invalid delegation nonce")]
+fn delegate_by_sig_cannot_be_replayed() {
+    let (wallet, signer) = permit_signer();
+    L1xFtErc20::new(metadata(), vec![signer], vec![U128(100)]);
+    let signature = sign_delegation(&wallet, bob(), 0, 2_000);
+
+    L1xFtErc20::ft_delegate_by_sig(
+        bob(),
+        U64(0),
+        U64(2_000),
+        signature.clone(),
+    );
+    L1xFtErc20::ft_delegate_by_sig(bob(), U64(0), U64(2_000), signature);
+}
+
+fn receiver() -> Address {
+    host::address(0xf7)
+}
+
+#[test]
+fn transfer_call_keeps_what_the_receiver_uses() {
+    setup();
+    host::mock_call(&receiver(), "ft_on_transfer", Some(br#""30""#.to_vec()));
+
+    host::set_caller(alice());
+    let used = L1xFtErc20::ft_transfer_call(
+        receiver(),
+        U128(100),
+        "deposit".to_string(),
+    );
+
+    assert_eq!(used.0, 70);
+    assert_eq!(balance(alice()), 930);
+    assert_eq!(balance(receiver()), 70);
+
+    let calls = host::calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].method_name, "ft_on_transfer");
+    let args: serde_json::Value =
+        serde_json::from_slice(&calls[0].args).unwrap();
+    assert_eq!(args["amount"], "100");
+    assert_eq!(args["msg"], "deposit");
+}
+
+#[test]
+fn transfer_call_refunds_everything_on_failure() {
+    setup();
+
+    host::set_caller(alice());
+    let used =
+        L1xFtErc20::ft_transfer_call(receiver(), U128(100), String::new());
+
+    assert_eq!(used.0, 0);
+    assert_eq!(balance(alice()), 1000);
+    assert_eq!(balance(receiver()), 0);
+}
+
+#[test]
+fn transfer_call_caps_the_refund_at_the_amount() {
+    setup();
+    host::mock_call(&receiver(), "ft_on_transfer", Some(br#""500""#.to_vec()));
+
+    host::set_caller(alice());
+    let used =
+        L1xFtErc20::ft_transfer_call(receiver(), U128(100), String::new());
+
+    assert_eq!(used.0, 0);
+    assert_eq!(balance(alice()), 1000);
+}
+
+fn treasury() -> Address {
+    host::address(0x7e)
+}
+
+/// Charges 10% on transfers, a fifth of which is burned.
+fn setup_with_fee() {
+    setup();
+    L1xFtErc20::ft_set_fee(1_000, 2_000, Some(treasury()));
+}
+
+#[test]
+fn transfer_charges_fee() {
+    setup_with_fee();
+    host::clear_captures();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(bob(), U128(100));
+
+    assert_eq!(balance(alice()), 900);
+    assert_eq!(balance(bob()), 590);
+    assert_eq!(balance(treasury()), 8);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1498);
+    assert_eq!(
+        host::decoded_events::<FtEvent>(),
+        vec![
+            FtEvent::Transfer { from: alice(), to: bob(), amount: U128(90) },
+            FtEvent::Transfer {
+                from: alice(),
+                to: treasury(),
+                amount: U128(8)
+            },
+            FtEvent::Burn { from: alice(), amount: U128(2) },
+            FtEvent::TransferFee {
+                from: alice(),
+                to: bob(),
+                gross: U128(100),
+                fee: U128(10),
+                burned: U128(2),
+                net: U128(90),
+            },
+        ]
+    );
+}
+
+#[test]
+fn transfer_from_charges_fee_on_the_spent_allowance() {
+    setup_with_fee();
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(bob(), U128(200));
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer_from(alice(), carol(), U128(100));
+
+    assert_eq!(L1xFtErc20::ft_allowance(alice(), bob()).0, 100);
+    assert_eq!(balance(alice()), 900);
+    assert_eq!(balance(carol()), 90);
+}
+
+#[test]
+fn exempt_accounts_pay_no_fee() {
+    setup_with_fee();
+    L1xFtErc20::ft_set_fee_exempt(vec![bob()], true);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(bob(), U128(100));
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer(carol(), U128(50));
+
+    assert_eq!(balance(bob()), 550);
+    assert_eq!(balance(carol()), 50);
+    assert_eq!(balance(treasury()), 0);
+
+    host::set_caller(owner());
+    L1xFtErc20::ft_set_fee_exempt(vec![bob()], false);
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer(carol(), U128(50));
+    assert_eq!(balance(carol()), 95);
+}
+
+#[test]
+#[should_panic(expected = "Not enough balance to transfer")]
+fn fee_is_part_of_the_transferred_amount() {
+    setup_with_fee();
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer(alice(), U128(501));
+}
+
+#[test]
+fn transfer_call_notifies_the_net_amount_and_refunds_without_fee() {
+    setup_with_fee();
+    host::mock_call(&receiver(), "ft_on_transfer", Some(br#""40""#.to_vec()));
+
+    host::set_caller(alice());
+    let used =
+        L1xFtErc20::ft_transfer_call(receiver(), U128(100), String::new());
+
+    let args: serde_json::Value =
+        serde_json::from_slice(&host::calls()[0].args).unwrap();
+    assert_eq!(args["amount"], "90");
+    assert_eq!(used.0, 50);
+    assert_eq!(balance(receiver()), 50);
+    assert_eq!(balance(alice()), 940);
+}
+
+#[test]
+fn fee_config_view() {
+    setup_with_fee();
+    L1xFtErc20::ft_set_fee_exempt(vec![carol()], true);
+
+    let config = serde_json::to_value(L1xFtErc20::ft_fee_config()).unwrap();
+    assert_eq!(config["rate_bps"], 1_000);
+    assert_eq!(config["burn_bps"], 2_000);
+    assert_eq!(config["treasury"], serde_json::to_value(treasury()).unwrap());
+    assert_eq!(config["exempt"], serde_json::to_value(vec![carol()]).unwrap());
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_set_fee() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_set_fee(100, 0, Some(alice()));
+}
+
+#[test]
+#[should_panic(expected = "rate_bps can't exceed 10000")]
+fn fee_rate_is_capped() {
+    setup();
+    L1xFtErc20::ft_set_fee(10_001, 0, Some(treasury()));
+}
+
+#[test]
+#[should_panic(
+    expected = "A treasury is required unless the whole fee is burned"
+)]
+fn fee_without_treasury_must_be_burned() {
+    setup();
+    L1xFtErc20::ft_set_fee(100, 5_000, None);
+}
+
+#[test]
+fn compliance_mode_defaults_to_open() {
+    setup();
+
+    assert_eq!(L1xFtErc20::ft_compliance_mode(), ComplianceMode::Open);
+    assert_eq!(
+        L1xFtErc20::ft_can_transfer(alice(), bob(), U128(1000)),
+        TransferRestriction::Ok
+    );
+    assert_eq!(
+        L1xFtErc20::ft_can_transfer(alice(), bob(), U128(1001)),
+        TransferRestriction::InsufficientBalance
+    );
+}
+
+#[test]
+#[should_panic(expected = "is blocklisted")]
+fn blocklisted_sender_cannot_transfer() {
+    setup();
+    L1xFtErc20::ft_set_blocklisted(vec![alice()], true);
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Blocklist);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(bob(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "is blocklisted")]
+fn blocklisted_recipient_cannot_be_minted_to() {
+    setup();
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Blocklist);
+    L1xFtErc20::ft_set_blocklisted(vec![carol()], true);
+
+    L1xFtErc20::ft_mint(carol(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "is blocklisted")]
+fn blocklisted_spender_cannot_be_approved() {
+    setup();
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Blocklist);
+    L1xFtErc20::ft_set_blocklisted(vec![bob()], true);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(bob(), U128(10));
+}
+
+#[test]
+fn blocklisted_owner_can_decrease_allowance() {
+    setup();
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(bob(), U128(10));
+    host::set_caller(owner());
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Blocklist);
+    L1xFtErc20::ft_set_blocklisted(vec![alice()], true);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_decrease_allowance(bob(), U128(5));
+
+    assert_eq!(L1xFtErc20::ft_allowance(alice(), bob()).0, 5);
+}
+
+#[test]
+fn blocklist_is_enforced_only_in_blocklist_mode() {
+    setup();
+    L1xFtErc20::ft_set_blocklisted(vec![bob()], true);
+
+    assert!(L1xFtErc20::ft_is_blocklisted(bob()));
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(bob(), U128(10));
+    assert_eq!(balance(bob()), 510);
+
+    host::set_caller(owner());
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Blocklist);
+    assert_eq!(
+        L1xFtErc20::ft_can_transfer(alice(), bob(), U128(10)),
+        TransferRestriction::RecipientBlocklisted
+    );
+    assert_eq!(
+        L1xFtErc20::ft_can_transfer(bob(), alice(), U128(10)),
+        TransferRestriction::SenderBlocklisted
+    );
+
+    L1xFtErc20::ft_set_blocklisted(vec![bob()], false);
+    assert_eq!(
+        L1xFtErc20::ft_can_transfer(alice(), bob(), U128(10)),
+        TransferRestriction::Ok
+    );
+}
+
+#[test]
+fn allowlist_mode_only_permits_listed_accounts() {
+    setup();
+    L1xFtErc20::ft_set_allowlisted(vec![alice(), bob()], true);
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Allowlist);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(bob(), U128(10));
+    assert_eq!(balance(bob()), 510);
+
+    assert_eq!(
+        L1xFtErc20::ft_can_transfer(alice(), carol(), U128(10)),
+        TransferRestriction::RecipientNotAllowlisted
+    );
+    assert_eq!(
+        L1xFtErc20::ft_can_transfer(carol(), alice(), U128(0)),
+        TransferRestriction::SenderNotAllowlisted
+    );
+}
+
+#[test]
+#[should_panic(expected = "is not allowlisted")]
+fn allowlist_mode_rejects_unlisted_recipient() {
+    setup();
+    L1xFtErc20::ft_set_allowlisted(vec![alice()], true);
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Allowlist);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(carol(), U128(10));
+}
+
+#[test]
+fn can_transfer_reports_pause() {
+    setup();
+    L1xFtErc20::ft_pause();
+
+    assert_eq!(
+        L1xFtErc20::ft_can_transfer(alice(), bob(), U128(1)),
+        TransferRestriction::Paused
+    );
+}
+
+#[test]
+fn can_transfer_reports_the_contract_instance_as_recipient() {
+    setup();
+
+    let escrow = host::contract_instance();
+
+    assert_eq!(
+        L1xFtErc20::ft_can_transfer(alice(), escrow, U128(1)),
+        TransferRestriction::RecipientIsContract
+    );
+}
+
+#[test]
+fn compliance_mode_change_emits_event() {
+    setup();
+    host::clear_captures();
+
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Allowlist);
+
+    assert_eq!(
+        host::decoded_events::<FtEvent>(),
+        vec![FtEvent::ComplianceModeChanged {
+            mode: ComplianceMode::Allowlist,
+            account: owner()
+        }]
+    );
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_edit_blocklist() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_set_blocklisted(vec![bob()], true);
+}
+
+fn vesting_escrow() -> Address {
+    host::contract_instance()
+}
+
+/// Vests 400 of the owner's tokens to carol over 1000 seconds from t=1000,
+/// with a 250 seconds cliff.
+fn setup_vesting(revocable: bool) -> U64 {
+    host::set_caller(owner());
+    L1xFtErc20::new(metadata(), vec![owner()], vec![U128(1000)]);
+    host::set_block(1, 500);
+
+    L1xFtErc20::ft_vesting_create(
+        carol(),
+        U128(400),
+        U64(1000),
+        U64(250),
+        U64(1000),
+        revocable,
+    )
+}
+
+#[test]
+fn vesting_create_moves_tokens_into_escrow() {
+    let id = setup_vesting(false);
+
+    assert_eq!(id.0, 0);
+    assert_eq!(balance(owner()), 600);
+    assert_eq!(balance(vesting_escrow()), 400);
+    assert_eq!(L1xFtErc20::ft_vesting_vested_amount(id).0, 0);
+
+    let schedule =
+        serde_json::to_value(L1xFtErc20::ft_vesting_schedule(id)).unwrap();
+    assert_eq!(schedule["total"], "400");
+    assert_eq!(schedule["revocable"], false);
+}
+
+#[test]
+fn vesting_escrow_is_not_a_holder() {
+    setup_vesting(false);
+
+    assert_eq!(L1xFtErc20::ft_holder_count(), 1);
+    assert_eq!(L1xFtErc20::ft_top_holders(10), vec![(owner(), U128(600))]);
+}
+
+#[test]
+fn vesting_escrow_carries_no_votes() {
+    host::set_caller(owner());
+    L1xFtErc20::new(metadata(), vec![owner()], vec![U128(1000)]);
+    L1xFtErc20::ft_delegate(bob());
+
+    L1xFtErc20::ft_vesting_create(
+        carol(),
+        U128(400),
+        U64(1000),
+        U64(250),
+        U64(1000),
+        false,
+    );
+
+    assert_eq!(L1xFtErc20::ft_get_votes(bob()).0, 600);
+    assert_eq!(L1xFtErc20::ft_get_votes(vesting_escrow()).0, 0);
+}
+
+#[test]
+#[should_panic(expected = "The contract instance can't be a delegate")]
+fn vesting_escrow_cannot_be_a_delegate() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_delegate(vesting_escrow());
+}
+
+#[test]
+#[should_panic(expected = "Tokens can't be sent to the contract instance")]
+fn tokens_cannot_be_sent_to_the_vesting_escrow() {
+    setup_vesting(false);
+
+    L1xFtErc20::ft_transfer(vesting_escrow(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "Tokens can't be sent to the contract instance")]
+fn tokens_cannot_be_minted_to_the_vesting_escrow() {
+    setup_vesting(false);
+
+    L1xFtErc20::ft_mint(vesting_escrow(), U128(1));
+}
+
+#[test]
+fn nothing_vests_before_the_cliff() {
+    let id = setup_vesting(false);
+
+    host::set_block(2, 1249);
+    assert_eq!(L1xFtErc20::ft_vesting_releasable_amount(id).0, 0);
+
+    host::set_block(3, 1250);
+    assert_eq!(L1xFtErc20::ft_vesting_releasable_amount(id).0, 100);
+}
+
+#[test]
+fn vesting_release_transfers_the_vested_part() {
+    let id = setup_vesting(false);
+
+    host::set_block(2, 1500);
+    host::set_caller(alice());
+    assert_eq!(L1xFtErc20::ft_vesting_release(id).0, 200);
+    assert_eq!(balance(carol()), 200);
+    assert_eq!(L1xFtErc20::ft_vesting_releasable_amount(id).0, 0);
+
+    host::set_block(3, 5000);
+    assert_eq!(L1xFtErc20::ft_vesting_release(id).0, 200);
+    assert_eq!(balance(carol()), 400);
+    assert_eq!(balance(vesting_escrow()), 0);
+}
+
+#[test]
+#[should_panic(expected = "No tokens to release")]
+fn vesting_release_rejects_nothing_to_release() {
+    let id = setup_vesting(false);
+
+    host::set_block(2, 1100);
+    L1xFtErc20::ft_vesting_release(id);
+}
+
+#[test]
+fn vesting_revoke_refunds_the_unvested_part() {
+    let id = setup_vesting(true);
+
+    host::set_block(2, 1300);
+    L1xFtErc20::ft_vesting_revoke(id);
+    assert_eq!(balance(owner()), 880);
+
+    host::set_block(3, 5000);
+    assert_eq!(L1xFtErc20::ft_vesting_vested_amount(id).0, 120);
+    L1xFtErc20::ft_vesting_release(id);
+    assert_eq!(balance(carol()), 120);
+    assert_eq!(balance(vesting_escrow()), 0);
+}
+
+#[test]
+#[should_panic(expected = "The vesting schedule isn't revocable")]
+fn irrevocable_vesting_cannot_be_revoked() {
+    let id = setup_vesting(false);
+    L1xFtErc20::ft_vesting_revoke(id);
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_revoke_vesting() {
+    let id = setup_vesting(true);
+
+    host::set_caller(carol());
+    L1xFtErc20::ft_vesting_revoke(id);
+}
+
+#[test]
+#[should_panic(expected = "Vesting cliff can't exceed the duration")]
+fn vesting_cliff_must_fit_the_duration() {
+    setup();
+    L1xFtErc20::ft_vesting_create(
+        carol(),
+        U128(1),
+        U64(0),
+        U64(11),
+        U64(10),
+        false,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Nonexistent vesting schedule 7")]
+fn vesting_views_reject_unknown_ids() {
+    setup();
+    L1xFtErc20::ft_vesting_vested_amount(U64(7));
+}
+
+#[test]
+fn vesting_emits_events() {
+    let id = setup_vesting(true);
+    host::set_block(2, 1500);
+    host::clear_captures();
+
+    L1xFtErc20::ft_vesting_release(id);
+    L1xFtErc20::ft_vesting_revoke(id);
+
+    let events = host::decoded_events::<FtEvent>();
+    assert!(events.contains(&FtEvent::VestingReleased {
+        id,
+        beneficiary: carol(),
+        amount: U128(200),
+    }));
+    assert!(
+        events.contains(&FtEvent::VestingRevoked { id, refunded: U128(200) })
+    );
+}
+
+#[test]
+#[should_panic(expected = "is blocklisted")]
+fn vesting_create_rejects_blocklisted_beneficiary() {
+    setup();
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Blocklist);
+    L1xFtErc20::ft_set_blocklisted(vec![carol()], true);
+
+    L1xFtErc20::ft_vesting_create(
+        carol(),
+        U128(1),
+        U64(0),
+        U64(0),
+        U64(10),
+        false,
+    );
+}
+
+#[test]
+#[should_panic(expected = "is not allowlisted")]
+fn vesting_create_rejects_unlisted_beneficiary() {
+    setup();
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Allowlist);
+
+    L1xFtErc20::ft_vesting_create(
+        carol(),
+        U128(1),
+        U64(0),
+        U64(0),
+        U64(10),
+        false,
+    );
+}
+
+#[test]
+#[should_panic(expected = "is blocklisted")]
+fn vesting_release_rejects_blocklisted_beneficiary() {
+    let id = setup_vesting(false);
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Blocklist);
+    L1xFtErc20::ft_set_blocklisted(vec![carol()], true);
+
+    host::set_block(2, 1500);
+    L1xFtErc20::ft_vesting_release(id);
+}
+
+#[test]
+#[should_panic(expected = "is not allowlisted")]
+fn vesting_release_rejects_unlisted_beneficiary() {
+    let id = setup_vesting(false);
+    L1xFtErc20::ft_set_compliance_mode(ComplianceMode::Allowlist);
+
+    host::set_block(2, 1500);
+    L1xFtErc20::ft_vesting_release(id);
+}
+
+#[test]
+fn holders_are_indexed_on_mint_and_transfer() {
+    setup();
+    assert_eq!(L1xFtErc20::ft_holder_count(), 2);
+
+    L1xFtErc20::ft_mint(carol(), U128(5));
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(bob(), U128(100));
+
+    assert_eq!(L1xFtErc20::ft_holder_count(), 3);
+    assert_eq!(
+        L1xFtErc20::ft_holders(0, 10),
+        vec![(alice(), U128(900)), (bob(), U128(600)), (carol(), U128(5))]
+    );
+}
+
+#[test]
+fn emptied_accounts_leave_the_holder_index() {
+    setup();
+    L1xFtErc20::ft_mint(carol(), U128(5));
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(bob(), U128(1000));
+
+    assert_eq!(L1xFtErc20::ft_holder_count(), 2);
+    assert_eq!(
+        L1xFtErc20::ft_holders(0, 10),
+        vec![(carol(), U128(5)), (bob(), U128(1500))]
+    );
+
+    host::set_caller(carol());
+    L1xFtErc20::ft_burn(U128(5));
+    assert_eq!(L1xFtErc20::ft_holders(0, 10), vec![(bob(), U128(1500))]);
+
+    host::set_caller(bob());
+    L1xFtErc20::ft_transfer(alice(), U128(1));
+    assert_eq!(L1xFtErc20::ft_holder_count(), 2);
+}
+
+#[test]
+fn top_holders_are_ranked_by_balance() {
+    setup();
+    L1xFtErc20::ft_mint(carol(), U128(700));
+
+    assert_eq!(
+        L1xFtErc20::ft_top_holders(2),
+        vec![(alice(), U128(1000)), (carol(), U128(700))]
+    );
+    assert_eq!(L1xFtErc20::ft_top_holders(10).len(), 3);
+}
+
+#[test]
+fn holders_are_paginated() {
+    setup();
+    L1xFtErc20::ft_mint(carol(), U128(5));
+
+    assert_eq!(L1xFtErc20::ft_holders(1, 1), vec![(bob(), U128(500))]);
+    assert_eq!(L1xFtErc20::ft_holders(2, 5), vec![(carol(), U128(5))]);
+    assert_eq!(L1xFtErc20::ft_holders(3, 5), vec![]);
+    assert_eq!(L1xFtErc20::ft_holders(1, u32::MAX).len(), 2);
+}
+
+#[test]
+fn allowances_of_lists_spenders() {
+    setup();
+    host::set_caller(alice());
+    L1xFtErc20::ft_approve(carol(), U128(30));
+    L1xFtErc20::ft_approve(bob(), U128(20));
+    L1xFtErc20::ft_approve(owner(), U128(0));
+
+    assert_eq!(
+        L1xFtErc20::ft_allowances_of(alice(), 0, 10),
+        vec![(bob(), U128(20)), (carol(), U128(30))]
+    );
+    assert_eq!(
+        L1xFtErc20::ft_allowances_of(alice(), 1, 1),
+        vec![(carol(), U128(30))]
+    );
+    assert_eq!(L1xFtErc20::ft_allowances_of(bob(), 0, 10), vec![]);
+}
+
+/// Layout 0 of the token, as written before the state was versioned.
+#[derive(BorshSerialize)]
+struct LegacyMetadata {
+    name: String,
+    decimals: u8,
+    symbol: String,
+    icon: Option<String>,
+}
+
+#[derive(BorshSerialize)]
+struct LegacyFt {
+    metadata: LegacyMetadata,
+    balances: LookupMap<Address, u128>,
+    allowances: LookupMap<Address, BTreeMap<Address, u128>>,
+    total_supply: u128,
+}
+
+/// Stores a layout 0 token with 700 tokens for alice and 300 for bob, and
+/// an allowance of 50 from alice to carol.
+fn deploy_legacy() {
+    let mut legacy = LegacyFt {
+        metadata: LegacyMetadata {
+            name: "Legacy".to_string(),
+            decimals: 18,
+            symbol: "LGC".to_string(),
+            icon: None,
+        },
+        balances: LookupMap::new(b"ab".to_vec()),
+        allowances: LookupMap::new(b"ac".to_vec()),
+        total_supply: 1000,
+    };
+    legacy.balances.insert(alice(), 700);
+    legacy.balances.insert(bob(), 300);
+    legacy.allowances.insert(alice(), BTreeMap::from([(carol(), 50)]));
+
+    l1x_sdk::storage_write(b"aa", &legacy.try_to_vec().unwrap());
+}
+
+#[test]
+fn new_writes_the_current_state_version() {
+    setup();
+    assert_eq!(L1xFtErc20::ft_state_version(), STATE_VERSION);
+}
+
+#[test]
+#[should_panic(
+    expected = "The stored state version 0 is outdated, the owner must call migrate"
+)]
+fn legacy_state_requires_migration() {
+    deploy_legacy();
+    L1xFtErc20::ft_total_supply();
+}
+
+#[test]
+fn migrate_upgrades_legacy_state() {
+    deploy_legacy();
+    assert_eq!(L1xFtErc20::ft_state_version(), 0);
+
+    host::set_caller(owner());
+    L1xFtErc20::migrate();
+
+    assert_eq!(L1xFtErc20::ft_state_version(), STATE_VERSION);
+    assert_eq!(L1xFtErc20::ft_name(), "Legacy");
+    assert_eq!(L1xFtErc20::ft_max_supply(), None);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1000);
+    assert_eq!(balance(alice()), 700);
+    assert_eq!(L1xFtErc20::ft_allowance(alice(), carol()).0, 50);
+    assert!(L1xFtErc20::ft_has_role(Role::Admin, owner()));
+    assert!(L1xFtErc20::ft_has_role(Role::Minter, owner()));
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_transfer(carol(), U128(100));
+    assert_eq!(balance(carol()), 100);
+    assert_eq!(L1xFtErc20::ft_unindexed_supply().0, 300);
+
+    host::set_caller(owner());
+    L1xFtErc20::ft_index_holders(vec![bob()]);
+    assert_eq!(L1xFtErc20::ft_unindexed_supply().0, 0);
+    assert_eq!(
+        L1xFtErc20::ft_holders(0, 10),
+        vec![(alice(), U128(600)), (carol(), U128(100)), (bob(), U128(300))]
+    );
+}
+
+#[test]
+fn index_holders_backfills_migrated_holders() {
+    deploy_legacy();
+    host::set_caller(owner());
+    L1xFtErc20::migrate();
+    assert_eq!(L1xFtErc20::ft_unindexed_supply().0, 1000);
+
+    L1xFtErc20::ft_index_holders(vec![alice(), carol()]);
+    L1xFtErc20::ft_index_holders(vec![alice()]);
+    assert_eq!(L1xFtErc20::ft_unindexed_supply().0, 300);
+
+    L1xFtErc20::ft_index_holders(vec![bob()]);
+    assert_eq!(
+        L1xFtErc20::ft_holders(0, 10),
+        vec![(alice(), U128(700)), (bob(), U128(300))]
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "300 tokens are held by accounts missing from the holder index"
+)]
+fn holder_views_wait_for_the_migrated_holders() {
+    deploy_legacy();
+    host::set_caller(owner());
+    L1xFtErc20::migrate();
+    L1xFtErc20::ft_index_holders(vec![alice()]);
+
+    L1xFtErc20::ft_top_holders(10);
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_index_holders() {
+    setup();
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_index_holders(vec![alice()]);
+}
+
+#[test]
+fn migrate_is_a_no_op_on_current_state() {
+    setup();
+    L1xFtErc20::migrate();
+
+    assert_eq!(balance(alice()), 1000);
+}
+
+#[test]
+#[should_panic(expected = "Only the owner can call this function")]
+fn non_owner_cannot_migrate() {
+    deploy_legacy();
+
+    host::set_caller(alice());
+    L1xFtErc20::migrate();
+}
+
+#[test]
+#[should_panic(
+    expected = "The stored state version 2 is newer than the supported version 1"
+)]
+fn newer_state_is_rejected() {
+    setup();
+    let mut bytes = host::storage_get(b"aa").unwrap();
+    bytes[4..6].copy_from_slice(&(STATE_VERSION + 1).to_le_bytes());
+    l1x_sdk::storage_write(b"aa", &bytes);
+
+    L1xFtErc20::ft_total_supply();
+}
+
+fn eth_bridge() -> Address {
+    host::address(0xe1)
+}
+
+fn eth_recipient() -> Address {
+    host::address(0xd1)
+}
+
+fn setup_bridge(mode: BridgeMode) {
+    setup();
+    L1xFtErc20::ft_set_bridge_mode(mode);
+    L1xFtErc20::ft_set_bridge_remote("ETH".to_string(), Some(eth_bridge()));
+}
+
+/// Base64 encoded `BridgeLocked` log of `bridge` crediting `amount` to
+/// `recipient`.
+fn bridge_locked_log(
+    bridge: Address,
+    global_tx_id: [u8; 32],
+    recipient: Address,
+    amount: u128,
+) -> String {
+    let log = Log {
+        address: ethers::types::Address::from_slice(bridge.as_bytes()),
+        topics: vec![
+            H256(keccak256("BridgeLocked(bytes32,address,address,uint256)")),
+            H256(global_tx_id),
+            H256::from(ethers::types::Address::repeat_byte(0x5e)),
+            H256::from(ethers::types::Address::from_slice(
+                recipient.as_bytes(),
+            )),
+        ],
+        data: encode(&[Token::Uint(EthU256::from(amount))]).into(),
+        ..Default::default()
+    };
+    base64::engine::general_purpose::STANDARD
+        .encode(serde_json::to_vec(&log).unwrap())
+}
+
+#[test]
+fn bridge_out_burns_and_records_a_payload() {
+    setup_bridge(BridgeMode::BurnMint);
+
+    host::set_caller(alice());
+    let global_tx_id = L1xFtErc20::ft_bridge_out(
+        "ETH".to_string(),
+        eth_recipient(),
+        U128(100),
+    );
+
+    assert_eq!(balance(alice()), 900);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1400);
+
+    let payload = L1xFtErc20::ft_bridge_payload(global_tx_id.clone());
+    assert_eq!(hex::encode(payload.global_tx_id), global_tx_id);
+    assert_eq!(payload.sender, alice());
+    assert_eq!(payload.dest_network, "ETH");
+    assert_eq!(payload.dest_address, eth_recipient());
+    assert_eq!(payload.dest_contract, eth_bridge());
+    assert_eq!(payload.amount, U128(100));
+
+    let expected_hash = keccak256(encode(&[
+        Token::FixedBytes(payload.global_tx_id.to_vec()),
+        Token::Address(ethers::types::Address::from_slice(
+            host::contract_instance().as_bytes(),
+        )),
+        Token::Uint(EthU256::from(L1X_CHAIN_ID)),
+        Token::String("ETH".to_string()),
+        Token::Address(ethers::types::Address::from_slice(
+            eth_bridge().as_bytes(),
+        )),
+        Token::Address(ethers::types::Address::from_slice(
+            eth_recipient().as_bytes(),
+        )),
+        Token::Uint(EthU256::from(100)),
+    ]));
+    assert_eq!(
+        L1xFtErc20::get_payload_hash_to_sign(format!("0x{}", global_tx_id)),
+        hex::encode(expected_hash)
+    );
+}
+
+#[test]
+fn bridge_out_uses_a_new_global_tx_id_every_time() {
+    setup_bridge(BridgeMode::BurnMint);
+
+    host::set_caller(alice());
+    let first =
+        L1xFtErc20::ft_bridge_out("ETH".to_string(), eth_recipient(), U128(1));
+    let second =
+        L1xFtErc20::ft_bridge_out("ETH".to_string(), eth_recipient(), U128(1));
+
+    assert_ne!(first, second);
+}
+
+#[test]
+#[should_panic(expected = "Unsupported network BSC")]
+fn bridge_out_rejects_unsupported_network() {
+    setup_bridge(BridgeMode::BurnMint);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_bridge_out("BSC".to_string(), eth_recipient(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "invalid global transaction id")]
+fn payload_hash_requires_known_global_tx_id() {
+    setup_bridge(BridgeMode::BurnMint);
+    L1xFtErc20::get_payload_hash_to_sign(hex::encode([7u8; 32]));
+}
+
+#[test]
+fn bridge_in_mints_from_bridge_locked_log() {
+    setup_bridge(BridgeMode::BurnMint);
+    host::clear_captures();
+
+    L1xFtErc20::save_event_data(
+        hex::encode([0x42; 32]),
+        U64(0),
+        bridge_locked_log(eth_bridge(), [0x42; 32], carol(), 250),
+    );
+
+    assert_eq!(balance(carol()), 250);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1750);
+    assert!(L1xFtErc20::ft_bridge_is_processed(hex::encode([0x42; 32])));
+    assert!(host::decoded_events::<FtEvent>().contains(&FtEvent::BridgeIn {
+        global_tx_id: [0x42; 32],
+        recipient: carol(),
+        amount: U128(250),
+    }));
+}
+
+#[test]
+#[should_panic(expected = "is already processed")]
+fn bridge_in_cannot_be_replayed() {
+    setup_bridge(BridgeMode::BurnMint);
+    let log = bridge_locked_log(eth_bridge(), [0x42; 32], carol(), 250);
+
+    L1xFtErc20::ft_bridge_in(hex::encode([0x42; 32]), log.clone());
+    L1xFtErc20::ft_bridge_in(hex::encode([0x42; 32]), log);
+}
+
+#[test]
+#[should_panic(expected = "doesn't match the log")]
+fn bridge_in_rejects_a_mismatched_global_tx_id() {
+    setup_bridge(BridgeMode::BurnMint);
+
+    L1xFtErc20::ft_bridge_in(
+        hex::encode([0x43; 32]),
+        bridge_locked_log(eth_bridge(), [0x42; 32], carol(), 250),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unknown bridge contract")]
+fn bridge_in_rejects_unknown_contracts() {
+    setup_bridge(BridgeMode::BurnMint);
+
+    L1xFtErc20::ft_bridge_in(
+        hex::encode([0x42; 32]),
+        bridge_locked_log(host::address(0x66), [0x42; 32], carol(), 250),
+    );
+}
+
+#[test]
+#[should_panic(expected = "is missing role Minter")]
+fn bridge_in_requires_minter() {
+    setup_bridge(BridgeMode::BurnMint);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_bridge_in(
+        hex::encode([0x42; 32]),
+        bridge_locked_log(eth_bridge(), [0x42; 32], alice(), 250),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unknown source id: 1")]
+fn save_event_data_rejects_unknown_source() {
+    setup_bridge(BridgeMode::BurnMint);
+
+    L1xFtErc20::save_event_data(
+        hex::encode([0x42; 32]),
+        U64(1),
+        bridge_locked_log(eth_bridge(), [0x42; 32], carol(), 250),
+    );
+}
+
+#[test]
+fn lock_mode_locks_and_unlocks() {
+    setup_bridge(BridgeMode::LockUnlock);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_bridge_out("ETH".to_string(), eth_recipient(), U128(300));
+    assert_eq!(balance(alice()), 700);
+    assert_eq!(balance(host::contract_instance()), 300);
+    assert_eq!(L1xFtErc20::ft_bridge_locked().0, 300);
+    assert_eq!(L1xFtErc20::ft_total_supply().0, 1500);
+
+    host::set_caller(owner());
+    L1xFtErc20::ft_bridge_in(
+        hex::encode([0x42; 32]),
+        bridge_locked_log(eth_bridge(), [0x42; 32], carol(), 120),
+    );
+    assert_eq!(balance(carol()), 120);
+    assert_eq!(L1xFtErc20::ft_bridge_locked().0, 180);
+}
+
+#[test]
+#[should_panic(expected = "Not enough locked tokens to unlock")]
+fn lock_mode_cannot_unlock_more_than_locked() {
+    setup_bridge(BridgeMode::LockUnlock);
+
+    L1xFtErc20::ft_bridge_in(
+        hex::encode([0x42; 32]),
+        bridge_locked_log(eth_bridge(), [0x42; 32], carol(), 1),
+    );
+}
+
+#[test]
+fn locked_tokens_are_not_held_or_voted() {
+    setup_bridge(BridgeMode::LockUnlock);
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_delegate(alice());
+    L1xFtErc20::ft_bridge_out("ETH".to_string(), eth_recipient(), U128(300));
+
+    assert_eq!(L1xFtErc20::ft_get_votes(alice()).0, 700);
+    assert_eq!(
+        L1xFtErc20::ft_top_holders(10),
+        vec![(alice(), U128(700)), (bob(), U128(500))]
+    );
+}
+
+/// Locks 300 of alice's tokens and vests 400 freshly minted tokens to
+/// carol over 1000 seconds from t=1000, all held by the contract instance.
+fn setup_bridge_and_vesting() {
+    setup_bridge(BridgeMode::LockUnlock);
+    L1xFtErc20::ft_mint(owner(), U128(400));
+    L1xFtErc20::ft_vesting_create(
+        carol(),
+        U128(400),
+        U64(1000),
+        U64(0),
+        U64(1000),
+        false,
+    );
+
+    host::set_caller(alice());
+    L1xFtErc20::ft_bridge_out("ETH".to_string(), eth_recipient(), U128(300));
+    host::set_caller(owner());
+}
+
+#[test]
+fn unlock_leaves_the_vesting_escrow_in_place() {
+    setup_bridge_and_vesting();
+    assert_eq!(balance(host::contract_instance()), 700);
+
+    L1xFtErc20::ft_bridge_in(
+        hex::encode([0x42; 32]),
+        bridge_locked_log(eth_bridge(), [0x42; 32], bob(), 300),
+    );
+    assert_eq!(L1xFtErc20::ft_bridge_locked().0, 0);
+    assert_eq!(balance(host::contract_instance()), 400);
+
+    host::set_block(2, 2000);
+    assert_eq!(L1xFtErc20::ft_vesting_release(U64(0)).0, 400);
+    assert_eq!(balance(host::contract_instance()), 0);
+}
+
+#[test]
+#[should_panic(expected = "Not enough locked tokens to unlock")]
+fn unlock_cannot_reach_the_vesting_escrow() {
+    setup_bridge_and_vesting();
+
+    L1xFtErc20::ft_bridge_in(
+        hex::encode([0x42; 32]),
+        bridge_locked_log(eth_bridge(), [0x42; 32], bob(), 301),
+    );
+}
+
+#[test]
+fn unlock_to_the_vesting_escrow_is_rejected() {
+    setup_bridge_and_vesting();
+
+    let result = host::transact(|| {
+        L1xFtErc20::ft_bridge_in(
+            hex::encode([0x42; 32]),
+            bridge_locked_log(
+                eth_bridge(),
+                [0x42; 32],
+                host::contract_instance(),
+                300,
+            ),
+        )
+    });
+
+    assert_eq!(
+        result.unwrap_err(),
+        "Tokens can't be sent to the contract instance"
+    );
+    assert_eq!(L1xFtErc20::ft_bridge_locked().0, 300);
+    assert_eq!(balance(host::contract_instance()), 700);
+    assert!(!L1xFtErc20::ft_bridge_is_processed(hex::encode([0x42; 32])));
+}
+
+#[test]
+#[should_panic(
+    expected = "Can't switch the bridge mode while tokens are locked"
+)]
+fn bridge_mode_is_fixed_while_tokens_are_locked() {
+    setup_bridge(BridgeMode::LockUnlock);
+    host::set_caller(alice());
+    L1xFtErc20::ft_bridge_out("ETH".to_string(), eth_recipient(), U128(1));
+
+    host::set_caller(owner());
+    L1xFtErc20::ft_set_bridge_mode(BridgeMode::BurnMint);
+}
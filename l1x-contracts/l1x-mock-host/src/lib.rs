@@ -0,0 +1,236 @@
+//! In-memory stand-in for the L1X VM host.
+//!
+//! Contracts call `l1x_sdk::storage_read`, `caller_address`, `call_contract`
+//! and friends, which end up in the host functions declared by `l1x-sys`.
+//! This crate provides native implementations of those functions backed by a
+//! thread-local [`MockHost`], so contract entry points can be called directly
+//! from `cargo test` without the devnet docker stack.
+//!
+//! Every test runs on its own thread, so each test gets a fresh host. Call
+//! [`reset`] if a test needs to start over.
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use borsh::BorshDeserialize;
+use l1x_sdk::types::Address;
+
+mod sys;
+
+/// Address used as the contract owner after [`reset`].
+pub const DEFAULT_OWNER: [u8; 20] = [0x0a; 20];
+
+/// Address used as the contract instance after [`reset`].
+pub const DEFAULT_INSTANCE: [u8; 20] = [0x0c; 20];
+
+/// A cross-contract call made by the contract under test.
+#[derive(Debug, Clone)]
+pub struct RecordedCall {
+    pub caller: Address,
+    pub contract_address: Address,
+    pub method_name: String,
+    pub args: Vec<u8>,
+    pub read_only: bool,
+}
+
+/// The host state shared by all `l1x-sys` functions.
+pub struct MockHost {
+    storage: HashMap<Vec<u8>, Vec<u8>>,
+    registers: HashMap<u64, Vec<u8>>,
+    input: Vec<u8>,
+    output: Option<Vec<u8>>,
+    caller: Address,
+    contract_owner: Address,
+    contract_instance: Address,
+    block_number: u64,
+    block_timestamp: u64,
+    block_hash: [u8; 32],
+    native_balances: BTreeMap<Address, u128>,
+    events: Vec<Vec<u8>>,
+    logs: Vec<String>,
+    call_responses: BTreeMap<(Address, String), VecDeque<Option<Vec<u8>>>>,
+    calls: Vec<RecordedCall>,
+}
+
+impl Default for MockHost {
+    fn default() -> Self {
+        Self {
+            storage: HashMap::new(),
+            registers: HashMap::new(),
+            input: Vec::new(),
+            output: None,
+            caller: Address::from(DEFAULT_OWNER),
+            contract_owner: Address::from(DEFAULT_OWNER),
+            contract_instance: Address::from(DEFAULT_INSTANCE),
+            block_number: 1,
+            block_timestamp: 1,
+            block_hash: [0u8; 32],
+            native_balances: BTreeMap::new(),
+            events: Vec::new(),
+            logs: Vec::new(),
+            call_responses: BTreeMap::new(),
+            calls: Vec::new(),
+        }
+    }
+}
+
+thread_local! {
+    static HOST: RefCell<MockHost> = RefCell::new(MockHost::default());
+}
+
+pub(crate) fn with_host<R>(f: impl FnOnce(&mut MockHost) -> R) -> R {
+    HOST.with(|host| f(&mut host.borrow_mut()))
+}
+
+/// Builds a test address filled with `byte`.
+pub fn address(byte: u8) -> Address {
+    Address::from([byte; 20])
+}
+
+/// Drops all state and restores the default owner, caller and instance.
+pub fn reset() {
+    with_host(|host| *host = MockHost::default());
+}
+
+/// Sets the address returned by `l1x_sdk::caller_address`.
+pub fn set_caller(caller: Address) {
+    with_host(|host| host.caller = caller);
+}
+
+/// Sets the address returned by `l1x_sdk::contract_owner_address`.
+pub fn set_contract_owner(owner: Address) {
+    with_host(|host| host.contract_owner = owner);
+}
+
+/// Sets the address returned by `l1x_sdk::contract_instance_address`.
+pub fn set_contract_instance(instance: Address) {
+    with_host(|host| host.contract_instance = instance);
+}
+
+pub fn caller() -> Address {
+    with_host(|host| host.caller)
+}
+
+pub fn contract_owner() -> Address {
+    with_host(|host| host.contract_owner)
+}
+
+pub fn contract_instance() -> Address {
+    with_host(|host| host.contract_instance)
+}
+
+/// Sets the values returned by `l1x_sdk::block_number` and
+/// `l1x_sdk::block_timestamp`.
+pub fn set_block(number: u64, timestamp: u64) {
+    with_host(|host| {
+        host.block_number = number;
+        host.block_timestamp = timestamp;
+    });
+}
+
+pub fn set_block_hash(hash: [u8; 32]) {
+    with_host(|host| host.block_hash = hash);
+}
+
+pub fn set_native_balance(account: &Address, amount: u128) {
+    with_host(|host| {
+        host.native_balances.insert(*account, amount);
+    });
+}
+
+pub fn native_balance(account: &Address) -> u128 {
+    with_host(|host| {
+        host.native_balances.get(account).copied().unwrap_or_default()
+    })
+}
+
+/// Sets the raw bytes returned by `l1x_sdk::input`.
+pub fn set_input(input: Vec<u8>) {
+    with_host(|host| host.input = input);
+}
+
+/// Returns the bytes passed to `l1x_sdk::output`, if any.
+pub fn output() -> Option<Vec<u8>> {
+    with_host(|host| host.output.clone())
+}
+
+pub fn storage_get(key: &[u8]) -> Option<Vec<u8>> {
+    with_host(|host| host.storage.get(key).cloned())
+}
+
+/// Raw payloads passed to `l1x_sdk::emit_event_experimental`, oldest first.
+pub fn events() -> Vec<Vec<u8>> {
+    with_host(|host| host.events.clone())
+}
+
+/// Borsh-decodes every emitted event as `T`.
+pub fn decoded_events<T: BorshDeserialize>() -> Vec<T> {
+    events()
+        .iter()
+        .map(|bytes| T::try_from_slice(bytes).expect("Can't decode the event"))
+        .collect()
+}
+
+/// Messages passed to `l1x_sdk::msg`, oldest first.
+pub fn logs() -> Vec<String> {
+    with_host(|host| host.logs.clone())
+}
+
+/// Forgets all captured events, logs and calls, keeping the storage.
+pub fn clear_captures() {
+    with_host(|host| {
+        host.events.clear();
+        host.logs.clear();
+        host.calls.clear();
+    });
+}
+
+/// Queues the value returned by the next `call_contract` to
+/// `contract_address::method_name`. Unscripted calls return `None`.
+pub fn mock_call(
+    contract_address: &Address,
+    method_name: &str,
+    response: Option<Vec<u8>>,
+) {
+    with_host(|host| {
+        host.call_responses
+            .entry((*contract_address, method_name.to_string()))
+            .or_default()
+            .push_back(response);
+    });
+}
+
+/// Cross-contract calls made so far, oldest first.
+pub fn calls() -> Vec<RecordedCall> {
+    with_host(|host| host.calls.clone())
+}
+
+/// Runs `f` like the VM runs a transaction: if it panics, the storage, the
+/// native balances and the captured events and logs are rolled back and the
+/// panic message is returned.
+pub fn transact<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    let snapshot = with_host(|host| {
+        (
+            host.storage.clone(),
+            host.native_balances.clone(),
+            host.events.clone(),
+            host.logs.clone(),
+        )
+    });
+
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(
+        |payload| {
+            with_host(|host| {
+                (host.storage, host.native_balances, host.events, host.logs) =
+                    snapshot;
+            });
+
+            match payload.downcast::<String>() {
+                Ok(message) => *message,
+                Err(payload) => payload
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .unwrap_or_default(),
+            }
+        },
+    )
+}
@@ -0,0 +1,264 @@
+//! Native implementations of the host functions imported by `l1x-sys`.
+//!
+//! The symbols and signatures mirror the `extern "C"` block of `l1x-sys`:
+//! pointers are passed as `u64`, variable-length results are handed back
+//! through registers and a missing value is signalled with `u64::MAX`.
+use borsh::BorshDeserialize;
+use l1x_sdk::contract_interaction::ContractCall;
+use l1x_sdk::types::Address;
+
+use crate::{with_host, RecordedCall};
+
+const NO_VALUE: u64 = u64::MAX;
+
+unsafe fn read_slice(len: u64, ptr: u64) -> Vec<u8> {
+    std::slice::from_raw_parts(ptr as *const u8, len as usize).to_vec()
+}
+
+unsafe fn read_address(len: u64, ptr: u64) -> Address {
+    Address::try_from(read_slice(len, ptr)).expect("Invalid address")
+}
+
+unsafe fn read_u128(ptr: u64) -> u128 {
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&read_slice(16, ptr));
+    u128::from_le_bytes(bytes)
+}
+
+fn set_register(register_id: u64, data: Vec<u8>) {
+    with_host(|host| {
+        host.registers.insert(register_id, data);
+    });
+}
+
+fn move_funds(from: Address, to: Address, amount: u128) -> u64 {
+    with_host(|host| {
+        let from_balance =
+            host.native_balances.get(&from).copied().unwrap_or_default();
+        if from_balance < amount {
+            return 0;
+        }
+        let to_balance =
+            host.native_balances.get(&to).copied().unwrap_or_default();
+        host.native_balances.insert(from, from_balance - amount);
+        host.native_balances.insert(to, to_balance + amount);
+        1
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn read_register(register_id: u64, ptr: u64) {
+    with_host(|host| {
+        let data = host
+            .registers
+            .get(&register_id)
+            .unwrap_or_else(|| panic!("Register {register_id} is empty"));
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                ptr as *mut u8,
+                data.len(),
+            );
+        }
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn register_len(register_id: u64) -> u64 {
+    with_host(|host| {
+        host.registers
+            .get(&register_id)
+            .map(|data| data.len() as u64)
+            .unwrap_or(NO_VALUE)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn input(register_id: u64) {
+    let input = with_host(|host| host.input.clone());
+    set_register(register_id, input);
+}
+
+#[no_mangle]
+pub extern "C" fn output(data_len: u64, data_ptr: u64) {
+    let data = unsafe { read_slice(data_len, data_ptr) };
+    with_host(|host| host.output = Some(data));
+}
+
+#[no_mangle]
+pub extern "C" fn msg(msg_len: u64, msg_ptr: u64) {
+    let msg = unsafe { read_slice(msg_len, msg_ptr) };
+    let msg = String::from_utf8_lossy(&msg).into_owned();
+    with_host(|host| host.logs.push(msg));
+}
+
+#[no_mangle]
+pub extern "C" fn panic() {
+    panic!("The contract panicked");
+}
+
+#[no_mangle]
+pub extern "C" fn panic_msg(msg_len: u64, msg_ptr: u64) {
+    let msg = unsafe { read_slice(msg_len, msg_ptr) };
+    panic!("{}", String::from_utf8_lossy(&msg));
+}
+
+#[no_mangle]
+pub extern "C" fn storage_write(
+    key_len: u64,
+    key_ptr: u64,
+    value_len: u64,
+    value_ptr: u64,
+    register_id: u64,
+) -> u64 {
+    let key = unsafe { read_slice(key_len, key_ptr) };
+    let value = unsafe { read_slice(value_len, value_ptr) };
+    match with_host(|host| host.storage.insert(key, value)) {
+        Some(evicted) => {
+            set_register(register_id, evicted);
+            1
+        }
+        None => 0,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn storage_read(
+    key_len: u64,
+    key_ptr: u64,
+    register_id: u64,
+) -> u64 {
+    let key = unsafe { read_slice(key_len, key_ptr) };
+    match with_host(|host| host.storage.get(&key).cloned()) {
+        Some(value) => {
+            set_register(register_id, value);
+            1
+        }
+        None => 0,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn storage_remove(
+    key_len: u64,
+    key_ptr: u64,
+    register_id: u64,
+) -> u64 {
+    let key = unsafe { read_slice(key_len, key_ptr) };
+    match with_host(|host| host.storage.remove(&key)) {
+        Some(evicted) => {
+            set_register(register_id, evicted);
+            1
+        }
+        None => 0,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn storage_has_key(key_len: u64, key_ptr: u64) -> u64 {
+    let key = unsafe { read_slice(key_len, key_ptr) };
+    with_host(|host| host.storage.contains_key(&key) as u64)
+}
+
+#[no_mangle]
+pub extern "C" fn caller_address(register_id: u64) {
+    let caller = with_host(|host| host.caller);
+    set_register(register_id, caller.to_vec());
+}
+
+#[no_mangle]
+pub extern "C" fn contract_owner_address(register_id: u64) {
+    let owner = with_host(|host| host.contract_owner);
+    set_register(register_id, owner.to_vec());
+}
+
+#[no_mangle]
+pub extern "C" fn contract_instance_address(register_id: u64) {
+    let instance = with_host(|host| host.contract_instance);
+    set_register(register_id, instance.to_vec());
+}
+
+#[no_mangle]
+pub extern "C" fn block_hash(register_id: u64) {
+    let hash = with_host(|host| host.block_hash);
+    set_register(register_id, hash.to_vec());
+}
+
+#[no_mangle]
+pub extern "C" fn block_number() -> u64 {
+    with_host(|host| host.block_number)
+}
+
+#[no_mangle]
+pub extern "C" fn block_timestamp() -> u64 {
+    with_host(|host| host.block_timestamp)
+}
+
+#[no_mangle]
+pub extern "C" fn address_balance(
+    address_len: u64,
+    address_ptr: u64,
+    register_id: u64,
+) {
+    let address = unsafe { read_address(address_len, address_ptr) };
+    let balance = crate::native_balance(&address);
+    set_register(register_id, balance.to_le_bytes().to_vec());
+}
+
+#[no_mangle]
+pub extern "C" fn transfer_to(
+    to_len: u64,
+    to_ptr: u64,
+    amount_ptr: u64,
+) -> u64 {
+    let to = unsafe { read_address(to_len, to_ptr) };
+    let amount = unsafe { read_u128(amount_ptr) };
+    let from = with_host(|host| host.contract_instance);
+    move_funds(from, to, amount)
+}
+
+#[no_mangle]
+pub extern "C" fn transfer_from_caller(amount_ptr: u64) -> u64 {
+    let amount = unsafe { read_u128(amount_ptr) };
+    let (from, to) = with_host(|host| (host.caller, host.contract_instance));
+    move_funds(from, to, amount)
+}
+
+#[no_mangle]
+pub extern "C" fn emit_event_experimental(event_len: u64, event_ptr: u64) {
+    let event = unsafe { read_slice(event_len, event_ptr) };
+    with_host(|host| host.events.push(event));
+}
+
+#[no_mangle]
+pub extern "C" fn call_contract(
+    call_len: u64,
+    call_ptr: u64,
+    register_id: u64,
+) -> u64 {
+    let call = unsafe { read_slice(call_len, call_ptr) };
+    let call = ContractCall::try_from_slice(&call)
+        .expect("Can't deserialize ContractCall");
+
+    let response = with_host(|host| {
+        host.calls.push(RecordedCall {
+            caller: host.contract_instance,
+            contract_address: call.contract_address,
+            method_name: call.method_name.clone(),
+            args: call.args.clone(),
+            read_only: call.read_only,
+        });
+        host.call_responses
+            .get_mut(&(call.contract_address, call.method_name))
+            .and_then(|responses| responses.pop_front())
+            .flatten()
+    });
+
+    match response {
+        Some(response) => {
+            set_register(register_id, response);
+            1
+        }
+        None => 0,
+    }
+}
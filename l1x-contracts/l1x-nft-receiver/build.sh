@@ -0,0 +1,3 @@
+#!/bin/bash
+cd "$(dirname "$0")"
+RUSTFLAGS='-C link-arg=-s' cargo build --target wasm32-unknown-unknown --release
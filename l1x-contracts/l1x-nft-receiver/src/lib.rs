@@ -0,0 +1,94 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk::store::LookupMap;
+use l1x_sdk::types::{Address, U128};
+use l1x_sdk::{caller_address, contract, contract_owner_address};
+
+const STORAGE_CONTRACT_KEY: &[u8] = b"state";
+const STORAGE_RECEIVED_KEY: &[u8] = b"received";
+
+/// Value `nft_on_received` returns to accept a token, see
+/// `l1x_nft::NFT_ON_RECEIVED_ACK`.
+const NFT_ON_RECEIVED_ACK: &str = "nft_on_received";
+
+/// Data asking the receiver to reject the token.
+const REJECT_DATA: &str = "reject";
+
+/// Example receiver for `NftContract::nft_safe_transfer_from`. It keeps the
+/// received tokens and remembers who sent them, unless the data asks it to
+/// reject the token.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct NftReceiver {
+    collection_address: Address,
+    received: LookupMap<u128, Address>,
+}
+
+#[contract]
+impl NftReceiver {
+    /// - `collection_address`: instance address of the accepted l1x-nft contract
+    pub fn new(collection_address: Address) {
+        assert_eq!(
+            caller_address(),
+            contract_owner_address(),
+            "Only the contract owner can call this method"
+        );
+
+        let mut contract = Self {
+            collection_address,
+            received: LookupMap::new(STORAGE_RECEIVED_KEY.to_vec()),
+        };
+        contract.save();
+    }
+
+    /// Called by the collection after token `id` has been transferred from
+    /// `from` by `operator`. Returns the acknowledgement to keep the token,
+    /// anything else makes the collection revert the transfer.
+    pub fn nft_on_received(
+        operator: Address,
+        from: Address,
+        id: U128,
+        data: String,
+    ) -> String {
+        let mut contract = Self::load();
+
+        assert_eq!(
+            caller_address(),
+            contract.collection_address,
+            "Only the accepted collection can call this method"
+        );
+
+        if data == REJECT_DATA {
+            l1x_sdk::msg(&format!("Rejecting token {} from {}", id.0, from));
+            return String::new();
+        }
+
+        contract.received.insert(id.0, from);
+        l1x_sdk::msg(&format!(
+            "Received token {} from {} by {}",
+            id.0, from, operator
+        ));
+
+        contract.save();
+
+        NFT_ON_RECEIVED_ACK.to_string()
+    }
+
+    /// Returns the account the token `id` was received from.
+    pub fn get_sender(id: U128) -> Option<Address> {
+        let contract = Self::load();
+        contract.received.get(&id.0).copied()
+    }
+
+    fn load() -> Self {
+        match l1x_sdk::storage_read(STORAGE_CONTRACT_KEY) {
+            Some(bytes) => Self::try_from_slice(&bytes).unwrap(),
+            None => panic!("The contract isn't initialized"),
+        }
+    }
+
+    fn save(&mut self) {
+        l1x_sdk::storage_write(
+            STORAGE_CONTRACT_KEY,
+            &self.try_to_vec().unwrap(),
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use l1x_mock_host as host;
+use l1x_nft_receiver::NftReceiver;
+use l1x_sdk::types::U128;
+
+fn setup() {
+    NftReceiver::new(host::address(0xc0));
+    host::set_caller(host::address(0xc0));
+}
+
+#[test]
+#[should_panic(expected = "Only the contract owner can call this method")]
+fn new_requires_the_owner() {
+    host::set_caller(host::address(1));
+
+    NftReceiver::new(host::address(0xc0));
+}
+
+#[test]
+fn on_received_keeps_the_token() {
+    setup();
+
+    let ack = NftReceiver::nft_on_received(
+        host::address(2),
+        host::address(1),
+        U128(7),
+        String::new(),
+    );
+
+    assert_eq!(ack, "nft_on_received");
+    assert_eq!(NftReceiver::get_sender(U128(7)), Some(host::address(1)));
+}
+
+#[test]
+fn on_received_rejects_on_request() {
+    setup();
+
+    let ack = NftReceiver::nft_on_received(
+        host::address(1),
+        host::address(1),
+        U128(7),
+        "reject".to_string(),
+    );
+
+    assert_eq!(ack, "");
+    assert_eq!(NftReceiver::get_sender(U128(7)), None);
+}
+
+#[test]
+#[should_panic(expected = "Only the accepted collection can call this method")]
+fn on_received_rejects_other_callers() {
+    setup();
+    host::set_caller(host::address(1));
+
+    NftReceiver::nft_on_received(
+        host::address(1),
+        host::address(1),
+        U128(7),
+        String::new(),
+    );
+}
@@ -0,0 +1,57 @@
+//! Events emitted by the collection through `l1x_sdk::emit_event_experimental`.
+//!
+//! The payload of every event is the Borsh encoding of [`NftEvent`], encoded
+//! and kept stable like the events of `l1x-ft`. The variants follow the
+//! ERC-721 and ERC-4906 events: mints are transfers from the zero address and
+//! burns are transfers to it.
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_access_control::{Role, RoleChange};
+use l1x_sdk::types::{Address, U128};
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+pub enum NftEvent {
+    /// Emitted when token `token_id` moves from `from` to `to`. `from` is the
+    /// zero address for mints and `to` is the zero address for burns.
+    Transfer { from: Address, to: Address, token_id: U128 },
+    /// Emitted when `owner` approves `approved` to transfer token `token_id`.
+    Approval { owner: Address, approved: Address, token_id: U128 },
+    /// Emitted when `owner` enables or disables `operator` to manage all of
+    /// its tokens.
+    ApprovalForAll { owner: Address, operator: Address, approved: bool },
+    /// Emitted when `account` halts transfers, mints, burns and approvals.
+    Paused { account: Address },
+    /// Emitted when `account` resumes the operations halted by `Paused`.
+    Unpaused { account: Address },
+    /// Emitted when the metadata URI of token `token_id` changes.
+    MetadataUpdate { token_id: U128 },
+    /// Emitted when the metadata URIs of the tokens `from_token_id` to
+    /// `to_token_id` change.
+    BatchMetadataUpdate { from_token_id: U128, to_token_id: U128 },
+    /// Emitted when `account` is granted `role` by `sender`.
+    RoleGranted { role: Role, account: Address, sender: Address },
+    /// Emitted when `role` is revoked from `account` by `sender`. `sender` is
+    /// `account` itself when the role is renounced.
+    RoleRevoked { role: Role, account: Address, sender: Address },
+}
+
+impl From<RoleChange> for NftEvent {
+    fn from(change: RoleChange) -> Self {
+        match change {
+            RoleChange::Granted { role, account, sender } => {
+                Self::RoleGranted { role, account, sender }
+            }
+            RoleChange::Revoked { role, account, sender } => {
+                Self::RoleRevoked { role, account, sender }
+            }
+        }
+    }
+}
@@ -1,10 +1,31 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_access_control::{RoleChange, Roles};
 use l1x_sdk::{
-    caller_address, contract, contract_owner_address, emit_event_experimental,
+    caller_address, contract, contract_instance_address,
+    contract_interaction::ContractCall,
+    contract_owner_address, emit_event_experimental,
     store::{LookupMap, Vector},
     types::{Address, U128, U64},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+pub use events::NftEvent;
+pub use l1x_access_control::Role;
+pub use l1x_eip712::L1X_CHAIN_ID;
+pub use minting::MintPolicy;
+pub use royalties::{Royalty, RoyaltySplit};
+pub use state::STATE_VERSION;
+pub use vouchers::MintVoucher;
+
+mod events;
+mod minting;
+mod royalties;
+mod state;
+mod vouchers;
+
+use minting::Minting;
+use royalties::Royalties;
+use vouchers::Vouchers;
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy)]
 struct OwnerInfo {
@@ -33,27 +54,49 @@ const STORAGE_OWNER_OF_KEY: &[u8] = b"owners";
 /// Key for the storage of approved data.
 const STORAGE_GET_APPROVED_KEY: &[u8] = b"approved";
 
-/// Key for the storage of the approval status data.
-const STORAGE_IS_APPROVED_FOR_ALL_KEY: &[u8] = b"approved-all";
+/// Key for the storage of the approval status data. Layout 0 stored it under
+/// `approved-all`, see `state::v0_to_v1`.
+const STORAGE_IS_APPROVED_FOR_ALL_KEY: &[u8] = b"approvals-for-all";
 
-/// Token Total Supply Configuration
-const L1X_NFT_TOTAL_SUPPLY: u128 = 10_000u128;
+/// Key for the storage of the operators approved by a user
+const STORAGE_OPERATORS_KEY: &[u8] = b"operators";
 
-#[derive(BorshSerialize, BorshDeserialize, Deserialize)]
+/// Key for the storage of the role members.
+const STORAGE_ROLES_KEY: &[u8] = b"roles";
+
+/// Key for the storage of the per-token URIs.
+const STORAGE_TOKEN_URIS_KEY: &[u8] = b"uris";
+
+/// Key for the storage of the global token index.
+const STORAGE_ALL_TOKENS_KEY: &[u8] = b"all-tokens";
+
+/// Key for the storage of the positions in the global token index.
+const STORAGE_ALL_TOKENS_INDEX_KEY: &[u8] = b"all-tokens-index";
+
+/// Key for the storage of the per-token royalties.
+const STORAGE_TOKEN_ROYALTIES_KEY: &[u8] = b"royalties";
+
+/// Key for the storage of the redeemed mint vouchers.
+const STORAGE_REDEEMED_VOUCHERS_KEY: &[u8] = b"vouchers";
+
+/// Key for the storage of the mint allowlist.
+const STORAGE_MINT_ALLOWLIST_KEY: &[u8] = b"mint-allowlist";
+
+/// Key for the storage of the tokens minted by every account.
+const STORAGE_MINTED_BY_KEY: &[u8] = b"minted-by";
+
+/// Value a receiver's `nft_on_received` returns to accept a token sent by
+/// `nft_safe_transfer_from`.
+pub const NFT_ON_RECEIVED_ACK: &str = "nft_on_received";
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 pub struct NFTMetadata {
     name: String,
     decimals: u8,
     symbol: String,
     icon: Option<String>,
-}
-
-#[derive(Debug, BorshSerialize, BorshDeserialize)]
-enum NftEvent {
-    NftTokenMinted(String),
-    NftTokenBurned(String),
-    NftTokenApproved(String),
-    NftTokenApprovedForAll(String),
-    NftTokenTransfered(String),
+    /// Prefix of the token URIs, see `nft_token_uri`
+    base_uri: Option<String>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
@@ -65,86 +108,635 @@ pub struct NftContract {
     owner_of: LookupMap<u128, OwnerInfo>,
     get_approved: LookupMap<u128, Address>,
     is_approved_for_all: LookupMap<Address, LookupMap<Address, bool>>,
+    roles: Roles,
+    paused: bool,
+    token_uris: LookupMap<u128, String>,
+    minting: Minting,
+    all_tokens: Vector<u128>,
+    all_tokens_index: LookupMap<u128, u32>,
+    royalties: Royalties,
+    force_burn_enabled: bool,
+    vouchers: Vouchers,
+    /// Number of tokens from before the global token index that are still
+    /// missing from it, see `nft_index_tokens`
+    unindexed_tokens: u64,
+    /// Highest id reported by `nft_index_burned_tokens`
+    last_unindexed_burn: u128,
+    /// The highest id ever minted, which bounds the metadata updates
+    highest_token_id: u128,
 }
 
 #[contract]
 impl NftContract {
-    pub fn new(metadata: NFTMetadata) {
+    /// Creates the collection. `max_supply` caps the token ids and the
+    /// number of minted tokens, `None` means an unlimited supply. Only
+    /// minters can mint until an admin sets another mint policy. Tokens pay
+    /// `default_royalty` on secondary sales unless they have their own.
+    pub fn new(
+        metadata: NFTMetadata,
+        max_supply: Option<U128>,
+        default_royalty: Option<Royalty>,
+    ) {
         assert_eq!(
             caller_address(),
             contract_owner_address(),
             "Only the contract owner can call this method"
         );
 
-        let mut contract = Self {
-            metadata,
-            current_token_id: 0u128,
-            minted_total: 0u64,
-            balance_of: LookupMap::new(STORAGE_BALANCE_OF_KEY.to_vec()),
-            owner_of: LookupMap::new(STORAGE_OWNER_OF_KEY.to_vec()),
-            get_approved: LookupMap::new(STORAGE_GET_APPROVED_KEY.to_vec()),
-            is_approved_for_all: LookupMap::new(STORAGE_IS_APPROVED_FOR_ALL_KEY.to_vec()),
-        };
+        let mut contract =
+            Self::with_metadata(metadata, max_supply.map(Into::into));
+        Self::grant_owner_roles(&mut contract.roles);
+        contract.royalties.set_default(default_royalty);
+
+        contract.save();
+    }
+
+    /// Upgrades the stored state of an instance deployed with an older
+    /// layout to the current one. Does nothing if it's already current.
+    pub fn migrate() {
+        assert_eq!(
+            caller_address(),
+            contract_owner_address(),
+            "Only the contract owner can call this method"
+        );
+
+        let (version, payload) = state::read(STORAGE_CONTRACT_KEY)
+            .expect("The contract isn't initialized");
+        if version == STATE_VERSION {
+            l1x_sdk::msg(&format!(
+                "The state is already at version {}",
+                version
+            ));
+            return;
+        }
+
+        // Convert the stored layout step by step
+        let mut contract = state::upgrade(version, payload);
+        l1x_sdk::msg(&format!(
+            "Migrated the state from version {} to {}",
+            version, STATE_VERSION
+        ));
+
+        // Save the contract state
+        contract.save();
+    }
+
+    pub fn nft_state_version() -> u16 {
+        match state::read(STORAGE_CONTRACT_KEY) {
+            Some((version, _)) => version,
+            None => panic!("The contract isn't initialized"),
+        }
+    }
+
+    pub fn nft_name() -> String {
+        let contract = Self::load();
+        contract.metadata.name
+    }
+
+    pub fn nft_symbol() -> String {
+        let contract = Self::load();
+        contract.metadata.symbol
+    }
+
+    pub fn nft_decimals() -> u8 {
+        let contract = Self::load();
+        contract.metadata.decimals
+    }
+
+    pub fn nft_metadata() -> NFTMetadata {
+        let contract = Self::load();
+        contract.metadata
+    }
+
+    /// Returns the URI of the token metadata: the base URI followed by the
+    /// token URI if one is set, or by the token id otherwise. Returns an
+    /// empty string if neither URI is set.
+    pub fn nft_token_uri(id: U128) -> String {
+        // load the contract storage state
+        let contract = Self::load();
+
+        // Call the internal implementation
+        contract.token_uri(id.into())
+    }
+
+    pub fn nft_set_base_uri(base_uri: Option<String>) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Call the internal implementation
+        contract.set_base_uri(base_uri);
+
+        // Save the contract state
+        contract.save();
+    }
+
+    pub fn nft_set_token_uri(id: U128, uri: Option<String>) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can change the metadata
+        contract.roles.assert_role(Role::Admin, &caller_address());
+
+        // Call the internal implementation
+        contract.set_token_uri(id.into(), uri);
+
+        // Save the contract state
+        contract.save();
+    }
+
+    /// Returns the receiver and the amount of the royalty due on a sale of
+    /// token `id` for `sale_price`. Returns the zero address and no amount
+    /// if the token pays no royalty.
+    pub fn nft_royalty_info(id: U128, sale_price: U128) -> (Address, U128) {
+        // load the contract storage state
+        let contract = Self::load();
+
+        // Panics if the token doesn't exist
+        contract.owner_of(id.into());
+
+        match contract.royalties.of(id.into()) {
+            Some(royalty) => {
+                (royalty.receiver, royalty.amount(sale_price.into()).into())
+            }
+            None => (Address::from([0u8; 20]), 0.into()),
+        }
+    }
+
+    /// Returns the share of every recipient of the royalty due on a sale of
+    /// token `id` for `sale_price`, see `nft_royalty_info`.
+    pub fn nft_royalty_splits(
+        id: U128,
+        sale_price: U128,
+    ) -> Vec<(Address, U128)> {
+        // load the contract storage state
+        let contract = Self::load();
+
+        // Panics if the token doesn't exist
+        contract.owner_of(id.into());
+
+        match contract.royalties.of(id.into()) {
+            Some(royalty) => royalty
+                .split_amounts(sale_price.into())
+                .into_iter()
+                .map(|(receiver, amount)| (receiver, amount.into()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn nft_default_royalty() -> Option<Royalty> {
+        let contract = Self::load();
+        contract.royalties.default
+    }
+
+    pub fn nft_set_default_royalty(royalty: Option<Royalty>) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can change the royalties
+        let caller_id = caller_address();
+        contract.roles.assert_role(Role::Admin, &caller_id);
+
+        contract.royalties.set_default(royalty);
+        l1x_sdk::msg(&format!(
+            "Default royalty set to {:?} by {}",
+            contract.royalties.default, caller_id
+        ));
+
+        // Save the contract state
+        contract.save();
+    }
+
+    /// Sets the royalty of token `id`, `None` makes it pay the default
+    /// royalty again.
+    pub fn nft_set_token_royalty(id: U128, royalty: Option<Royalty>) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can change the royalties
+        let caller_id = caller_address();
+        contract.roles.assert_role(Role::Admin, &caller_id);
+
+        // Panics if the token doesn't exist
+        contract.owner_of(id.into());
+
+        l1x_sdk::msg(&format!(
+            "Royalty of token_id {:#?} set to {:?} by {}",
+            id.0, royalty, caller_id
+        ));
+        contract.royalties.set_token(id.into(), royalty);
+
+        // Save the contract state
+        contract.save();
+    }
+
+    pub fn nft_minted_total() -> U64 {
+        let contract = Self::load();
+        contract.minted_total.into()
+    }
+
+    pub fn nft_max_supply() -> Option<U128> {
+        let contract = Self::load();
+        contract.minting.max_supply.map(Into::into)
+    }
+
+    /// Returns the number of tokens that can still be minted, `None` if the
+    /// supply is unlimited.
+    pub fn nft_remaining_supply() -> Option<U128> {
+        let contract = Self::load();
+        contract.minting.remaining_supply(contract.minted_total).map(Into::into)
+    }
+
+    pub fn nft_mint_policy() -> MintPolicy {
+        let contract = Self::load();
+        contract.minting.policy
+    }
+
+    /// Returns the start and end timestamps of the mint.
+    pub fn nft_mint_window() -> (Option<U64>, Option<U64>) {
+        let contract = Self::load();
+        (
+            contract.minting.start.map(Into::into),
+            contract.minting.end.map(Into::into),
+        )
+    }
+
+    pub fn nft_is_mint_allowlisted(account: Address) -> bool {
+        let contract = Self::load();
+        contract.minting.is_allowlisted(&account)
+    }
+
+    /// Returns the number of tokens `account` minted under the mint policy.
+    pub fn nft_minted_by(account: Address) -> u32 {
+        let contract = Self::load();
+        contract.minting.minted_by(&account)
+    }
+
+    pub fn nft_set_mint_policy(policy: MintPolicy) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can change the mint policy
+        let caller_id = caller_address();
+        contract.roles.assert_role(Role::Admin, &caller_id);
+
+        contract.minting.set_policy(policy);
+        l1x_sdk::msg(&format!(
+            "Mint policy set to {:?} by {}",
+            contract.minting.policy, caller_id
+        ));
+
+        // Save the contract state
+        contract.save();
+    }
+
+    pub fn nft_set_mint_window(start: Option<U64>, end: Option<U64>) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can change the mint window
+        let caller_id = caller_address();
+        contract.roles.assert_role(Role::Admin, &caller_id);
+
+        contract
+            .minting
+            .set_window(start.map(|start| start.0), end.map(|end| end.0));
+        l1x_sdk::msg(&format!(
+            "Mint window set to {:?}..{:?} by {}",
+            contract.minting.start, contract.minting.end, caller_id
+        ));
+
+        // Save the contract state
+        contract.save();
+    }
+
+    pub fn nft_set_mint_allowlisted(accounts: Vec<Address>, allowlisted: bool) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can change the mint allowlist
+        contract.roles.assert_role(Role::Admin, &caller_address());
+
+        for account in accounts {
+            contract.minting.set_allowlisted(account, allowlisted);
+        }
+
+        // Save the contract state
+        contract.save();
+    }
+
+    pub fn nft_mint_to(to: Address) -> U128 {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Minters mint freely, everybody else by the mint policy
+        contract.authorize_mint(1);
+
+        // Call the internal implementation
+        let new_token_id = contract.mint_to(to);
+
+        // Save the contract state
+        contract.save();
+
+        new_token_id.into()
+    }
+
+    pub fn nft_mint_to_with_uri(to: Address, uri: String) -> U128 {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Minters mint freely, everybody else by the mint policy
+        contract.authorize_mint(1);
+
+        // Call the internal implementation
+        let new_token_id = contract.mint_to(to);
+        contract.set_token_uri(new_token_id, Some(uri));
+
+        // Save the contract state
+        contract.save();
+
+        new_token_id.into()
+    }
+
+    pub fn nft_mint_id_to(to: Address, id: U128) -> U128 {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Minters mint freely, everybody else by the mint policy
+        contract.authorize_mint(1);
+
+        // Call the internal implementation
+        let new_token_id = contract.mint_id_to(to, id.into());
+
+        // Save the contract state
+        contract.save();
+
+        new_token_id.into()
+    }
+
+    /// Mints `count` tokens with the next free ids to `to`.
+    pub fn nft_batch_mint(to: Address, count: u32) -> Vec<U128> {
+        assert!(count > 0, "The batch is empty");
+
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Minters mint freely, everybody else by the mint policy
+        contract.authorize_mint(count);
+
+        // Call the internal implementation
+        let new_token_ids =
+            (0..count).map(|_| contract.mint_to(to).into()).collect();
+
+        // Save the contract state
+        contract.save();
+
+        new_token_ids
+    }
+
+    /// Mints the tokens `ids` to `to`.
+    pub fn nft_batch_mint_ids(to: Address, ids: Vec<U128>) -> Vec<U128> {
+        assert!(!ids.is_empty(), "The batch is empty");
+        let count = u32::try_from(ids.len()).expect("The batch is too large");
+
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Minters mint freely, everybody else by the mint policy
+        contract.authorize_mint(count);
+
+        // Call the internal implementation
+        let new_token_ids = ids
+            .into_iter()
+            .map(|id| contract.mint_id_to(to, id.into()).into())
+            .collect();
+
+        // Save the contract state
+        contract.save();
+
+        new_token_ids
+    }
+
+    /// Mints the token of `voucher` to its recipient. The caller pays the
+    /// voucher's `min_price`, which is forwarded to the voucher signer. The
+    /// mint policy doesn't apply, the max supply does.
+    ///
+    /// - `signature`: hex encoded secp256k1 signature of the digest returned
+    ///   by `nft_voucher_digest`
+    pub fn nft_redeem_voucher(voucher: MintVoucher, signature: String) -> U128 {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        assert!(
+            l1x_sdk::block_timestamp() <= voucher.expiry.0,
+            "The voucher has expired"
+        );
+
+        let digest = voucher
+            .digest(&contract.metadata.name, &contract_instance_address());
+        let signer = contract.vouchers.redeem(digest, &signature);
+
+        // Collect the price and forward it to the signer
+        let price = voucher.min_price.0;
+        if price > 0 {
+            Self::collect_payment(price, &signer);
+        }
+
+        // Call the internal implementation
+        let new_token_id =
+            contract.mint_id_to(voucher.recipient, voucher.token_id.into());
+        if !voucher.uri.is_empty() {
+            contract.set_token_uri(new_token_id, Some(voucher.uri));
+        }
+
+        // Save the contract state
+        contract.save();
+
+        new_token_id.into()
+    }
+
+    /// Returns the hex encoded digest the voucher signer has to sign for
+    /// `nft_redeem_voucher`.
+    pub fn nft_voucher_digest(voucher: MintVoucher) -> String {
+        let contract = Self::load();
+        hex::encode(
+            voucher
+                .digest(&contract.metadata.name, &contract_instance_address()),
+        )
+    }
+
+    pub fn nft_is_voucher_redeemed(voucher: MintVoucher) -> bool {
+        let contract = Self::load();
+        let digest = voucher
+            .digest(&contract.metadata.name, &contract_instance_address());
+        contract.vouchers.is_redeemed(&digest)
+    }
+
+    pub fn nft_voucher_signer() -> Option<Address> {
+        let contract = Self::load();
+        contract.vouchers.signer
+    }
+
+    /// Sets the key vouchers must be signed with, `None` disables vouchers.
+    pub fn nft_set_voucher_signer(signer: Option<Address>) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can change the voucher signer
+        let caller_id = caller_address();
+        contract.roles.assert_role(Role::Admin, &caller_id);
+
+        contract.vouchers.signer = signer;
+        l1x_sdk::msg(&format!(
+            "Voucher signer set to {:?} by {}",
+            signer, caller_id
+        ));
+
+        // Save the contract state
+        contract.save();
+    }
+
+    pub fn nft_burn(id: U128) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Call the internal implementation
+        contract.burn(id.into());
+
+        // Save the contract state
+        contract.save();
+    }
+
+    /// Burns token `id` whoever holds it. Only burners can, and only while
+    /// force burning is enabled.
+    pub fn nft_force_burn(id: U128) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Call the internal implementation
+        contract.force_burn(id.into());
+
+        // Save the contract state
+        contract.save();
+    }
+
+    pub fn nft_set_force_burn_enabled(enabled: bool) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can enable force burning
+        let caller_id = caller_address();
+        contract.roles.assert_role(Role::Admin, &caller_id);
+
+        contract.force_burn_enabled = enabled;
+        l1x_sdk::msg(&format!(
+            "Force burning enabled set to {} by {}",
+            enabled, caller_id
+        ));
+
+        // Save the contract state
         contract.save();
     }
 
-    pub fn nft_name() -> String {
+    pub fn nft_force_burn_enabled() -> bool {
         let contract = Self::load();
-        contract.metadata.name
+        contract.force_burn_enabled
     }
 
-    pub fn nft_symbol() -> String {
-        let contract = Self::load();
-        contract.metadata.symbol
+    pub fn nft_batch_burn(ids: Vec<U128>) {
+        assert!(!ids.is_empty(), "The batch is empty");
+
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Call the internal implementation for every token
+        for id in ids {
+            contract.burn(id.into());
+        }
+
+        // Save the contract state
+        contract.save();
     }
 
-    pub fn nft_decimals() -> u8 {
-        let contract = Self::load();
-        contract.metadata.decimals
+    pub fn nft_grant_role(role: Role, account: Address) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can grant roles
+        Self::emit_role_change(contract.roles.grant_role(
+            &caller_address(),
+            role,
+            account,
+        ));
+
+        // Save the contract state
+        contract.save();
     }
 
-    pub fn nft_minted_total() -> U64 {
-        let contract = Self::load();
-        contract.minted_total.into()
+    pub fn nft_revoke_role(role: Role, account: Address) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can revoke roles
+        Self::emit_role_change(contract.roles.revoke_role(
+            &caller_address(),
+            role,
+            account,
+        ));
+
+        // Save the contract state
+        contract.save();
     }
 
-    pub fn nft_mint_to(to: Address) -> U128 {
+    pub fn nft_renounce_role(role: Role) {
         // load the contract storage state
         let mut contract = Self::load();
 
-        // Call the internal implementation
-        let new_token_id = contract.mint_to(to);
+        // The caller drops its own role
+        Self::emit_role_change(
+            contract.roles.renounce_role(&caller_address(), role),
+        );
 
         // Save the contract state
         contract.save();
+    }
 
-        new_token_id.into()
+    pub fn nft_has_role(role: Role, account: Address) -> bool {
+        // load the contract storage state
+        let contract = Self::load();
+
+        contract.roles.has_role(role, &account)
     }
 
-    pub fn nft_mint_id_to(to: Address, id: U128) -> U128 {
+    pub fn nft_pause() {
         // load the contract storage state
         let mut contract = Self::load();
 
         // Call the internal implementation
-        let new_token_id = contract.mint_id_to(to, id.into());
+        contract.set_paused(true);
 
         // Save the contract state
         contract.save();
-
-        new_token_id.into()
     }
 
-    pub fn nft_burn(id: U128) {
+    pub fn nft_unpause() {
         // load the contract storage state
         let mut contract = Self::load();
 
         // Call the internal implementation
-        contract.burn(id.into());
+        contract.set_paused(false);
 
         // Save the contract state
         contract.save();
     }
 
+    pub fn nft_is_paused() -> bool {
+        // load the contract storage state
+        let contract = Self::load();
+
+        contract.paused
+    }
+
     pub fn nft_approve(spender: Address, id: U128) {
         // load the contract storage state
         let mut contract = Self::load();
@@ -178,6 +770,44 @@ impl NftContract {
         contract.save();
     }
 
+    pub fn nft_batch_transfer_from(from: Address, to: Address, ids: Vec<U128>) {
+        assert!(!ids.is_empty(), "The batch is empty");
+
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Call the internal implementation for every token
+        for id in ids {
+            contract.transfer_from(from, to, id.into());
+        }
+
+        // Save the contract state
+        contract.save();
+    }
+
+    /// Transfers the token like `nft_transfer_from`, then calls
+    /// `nft_on_received(operator, from, id, data)` on `to` and reverts if it
+    /// doesn't answer [`NFT_ON_RECEIVED_ACK`], including when the call
+    /// fails. Accounts without contract code can't answer, send them tokens
+    /// with `nft_transfer_from`.
+    pub fn nft_safe_transfer_from(
+        from: Address,
+        to: Address,
+        id: U128,
+        data: String,
+    ) {
+        let operator = caller_address();
+
+        // Persisted before the callback, as in `ft_transfer_call`
+        {
+            let mut contract = Self::load();
+            contract.transfer_from(from, to, id.into());
+            contract.save();
+        }
+
+        Self::call_on_received(&operator, &from, &to, id.into(), data);
+    }
+
     pub fn nft_balance_of(owner: Address) -> U128 {
         // load the contract storage state
         let contract = Self::load();
@@ -194,20 +824,179 @@ impl NftContract {
         contract.owner_of(id.into())
     }
 
+    /// Returns all the tokens of `owner`, see `nft_tokens_of_owner` for
+    /// large balances.
     pub fn nft_owned_tokens(owner: Address) -> Vec<U128> {
         // load the contract storage state
         let contract = Self::load();
 
         // Call the internal implementation
-        contract.owned_tokens(owner)
+        contract.tokens_of_owner(owner, 0, u32::MAX)
+    }
+
+    /// Returns up to `limit` tokens of `owner`, starting at position
+    /// `from_index` of its tokens. The order changes when tokens leave.
+    pub fn nft_tokens_of_owner(
+        owner: Address,
+        from_index: u32,
+        limit: u32,
+    ) -> Vec<U128> {
+        // load the contract storage state
+        let contract = Self::load();
+
+        // Call the internal implementation
+        contract.tokens_of_owner(owner, from_index, limit)
+    }
+
+    /// Returns the number of existing tokens. Panics while the tokens of a
+    /// migrated collection are being indexed, see `nft_index_tokens`, which
+    /// also holds back mints and burns.
+    pub fn nft_total_supply() -> U128 {
+        // load the contract storage state
+        let contract = Self::load();
+        contract.assert_token_index_complete();
+
+        u128::from(contract.all_tokens.len()).into()
+    }
+
+    /// Returns the token at position `index` of the global token index. The
+    /// order changes when tokens are burned. See `nft_total_supply` for
+    /// migrated collections.
+    pub fn nft_token_by_index(index: u32) -> U128 {
+        // load the contract storage state
+        let contract = Self::load();
+        contract.assert_token_index_complete();
+
+        let id = contract.all_tokens.get(index).unwrap_or_else(|| {
+            panic!("Index {} is out of bounds of the token index", index)
+        });
+        (*id).into()
+    }
+
+    /// Adds the tokens of `owners` to the global token index, which is empty
+    /// after a migration from layout 0, with owners found off-chain as for
+    /// `ft_index_holders` of l1x-ft. The enumeration views work again once
+    /// `nft_unindexed_tokens` is zero.
+    pub fn nft_index_tokens(owners: Vec<Address>) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can index the tokens
+        contract.roles.assert_role(Role::Admin, &caller_address());
+
+        for owner in owners {
+            contract.index_tokens_of(&owner);
+        }
+
+        // Save the contract state
+        contract.save();
+    }
+
+    /// Takes the tokens burned before a migration from layout 0 off the
+    /// unindexed tokens, since layout 0 counted them as minted. `ids` must
+    /// be ascending and above the ids reported before, so every burn counts
+    /// once. Nothing is minted or burned until the index is complete, so
+    /// every token without an owner was burned before the migration.
+    pub fn nft_index_burned_tokens(ids: Vec<U128>) {
+        // load the contract storage state
+        let mut contract = Self::load();
+
+        // Only admins can index the tokens
+        contract.roles.assert_role(Role::Admin, &caller_address());
+
+        for id in ids {
+            let id = id.0;
+            assert!(
+                id > contract.last_unindexed_burn,
+                "The burned token {} is out of order or already reported",
+                id
+            );
+            assert!(
+                !contract.owner_of.contains_key(&id),
+                "Token {} isn't burned",
+                id
+            );
+            contract.count_indexed_token();
+            contract.last_unindexed_burn = id;
+        }
+
+        // Save the contract state
+        contract.save();
+    }
+
+    /// Returns the number of existing tokens missing from the global token
+    /// index after a migration.
+    pub fn nft_unindexed_tokens() -> U64 {
+        // load the contract storage state
+        let contract = Self::load();
+
+        contract.unindexed_tokens.into()
     }
 }
 
 impl NftContract {
+    fn with_metadata(metadata: NFTMetadata, max_supply: Option<u128>) -> Self {
+        Self {
+            metadata,
+            current_token_id: 0u128,
+            minted_total: 0u64,
+            balance_of: LookupMap::new(STORAGE_BALANCE_OF_KEY.to_vec()),
+            owner_of: LookupMap::new(STORAGE_OWNER_OF_KEY.to_vec()),
+            get_approved: LookupMap::new(STORAGE_GET_APPROVED_KEY.to_vec()),
+            is_approved_for_all: LookupMap::new(STORAGE_IS_APPROVED_FOR_ALL_KEY.to_vec()),
+            roles: Roles::new(STORAGE_ROLES_KEY.to_vec()),
+            paused: false,
+            token_uris: LookupMap::new(STORAGE_TOKEN_URIS_KEY.to_vec()),
+            minting: Minting::new(
+                max_supply,
+                MintPolicy::MinterOnly,
+                STORAGE_MINT_ALLOWLIST_KEY.to_vec(),
+                STORAGE_MINTED_BY_KEY.to_vec(),
+            ),
+            all_tokens: Vector::new(STORAGE_ALL_TOKENS_KEY.to_vec()),
+            all_tokens_index: LookupMap::new(
+                STORAGE_ALL_TOKENS_INDEX_KEY.to_vec(),
+            ),
+            royalties: Royalties::new(
+                None,
+                STORAGE_TOKEN_ROYALTIES_KEY.to_vec(),
+            ),
+            force_burn_enabled: false,
+            vouchers: Vouchers::new(STORAGE_REDEEMED_VOUCHERS_KEY.to_vec()),
+            unindexed_tokens: 0u64,
+            last_unindexed_burn: 0u128,
+            highest_token_id: 0u128,
+        }
+    }
+
+    fn grant_owner_roles(roles: &mut Roles) {
+        // The deployer administers the contract and may mint, burn and pause
+        let owner = contract_owner_address();
+        for role in [Role::Admin, Role::Minter, Role::Burner, Role::Pauser] {
+            Self::emit_role_change(
+                roles.internal_grant_role(&owner, role, owner),
+            );
+        }
+    }
+
+    /// Emits the change of the role members reported by `Roles`, if any.
+    fn emit_role_change(change: Option<RoleChange>) {
+        if let Some(change) = change {
+            emit_event_experimental(NftEvent::from(change));
+        }
+    }
+
     fn internal_new_balance_vec(&self, address: &Address) -> Vector<u128> {
         Vector::<u128>::new([&address.to_vec(), STORAGE_BALANCE_IDS_KEY].concat())
     }
 
+    fn internal_new_operators_map(
+        &self,
+        owner: &Address,
+    ) -> LookupMap<Address, bool> {
+        LookupMap::new([&owner.to_vec(), STORAGE_OPERATORS_KEY].concat())
+    }
+
     fn internal_remove_token(&mut self, id: u128) -> (Address, u32) {
         // Update the balances
         let owner_info = self
@@ -262,19 +1051,83 @@ impl NftContract {
         self.owner_of.insert(id, OwnerInfo::new(to, last_idx));
     }
 
+    fn internal_add_to_all_tokens(&mut self, id: u128) {
+        self.all_tokens.push(id);
+        self.all_tokens_index.insert(id, self.all_tokens.len() - 1);
+    }
+
+    fn internal_remove_from_all_tokens(&mut self, id: u128) {
+        let idx = match self.all_tokens_index.get(&id) {
+            Some(idx) => *idx,
+            None => panic!("Token {} isn't in the token index", id),
+        };
+
+        let is_last = idx == self.all_tokens.len() - 1;
+        // The removed token is replaced by the last one of the index
+        self.all_tokens.swap_remove(idx);
+        if !is_last {
+            // Update the position of the moved token
+            let swapped_token_id = *self
+                .all_tokens
+                .get(idx)
+                .expect("Can't get the swapped token_id");
+            self.all_tokens_index.insert(swapped_token_id, idx);
+        }
+
+        self.all_tokens_index.remove(id);
+    }
+
+    /// Adds the tokens of `owner` that are missing from the global token
+    /// index.
+    fn index_tokens_of(&mut self, owner: &Address) {
+        let missing_ids: Vec<u128> = match self.balance_of.get(owner) {
+            Some(issued_tokens) => (0..issued_tokens.len())
+                .map(|idx| issued_tokens.get(idx).copied().unwrap())
+                .filter(|id| !self.all_tokens_index.contains_key(id))
+                .collect(),
+            None => return,
+        };
+
+        for id in missing_ids {
+            self.internal_add_to_all_tokens(id);
+            self.highest_token_id = self.highest_token_id.max(id);
+            self.count_indexed_token();
+        }
+    }
+
+    /// Takes a token from before the global token index off the unindexed
+    /// tokens.
+    fn count_indexed_token(&mut self) {
+        self.unindexed_tokens = self
+            .unindexed_tokens
+            .checked_sub(1)
+            .expect("The unindexed tokens are underflowed");
+    }
+
     fn mint_id_to(&mut self, to: Address, id: u128) -> u128 {
+        self.assert_not_paused();
+        self.assert_token_index_complete();
+
         let new_token_id = id;
-        assert!(new_token_id <= L1X_NFT_TOTAL_SUPPLY, "Max supply reached");
+        assert!(
+            !self.owner_of.contains_key(&new_token_id),
+            "Token ID {:#?} is already minted",
+            new_token_id
+        );
+        self.minting.assert_within_max_supply(new_token_id, self.minted_total);
 
         self.internal_add_token_to(to, new_token_id);
+        self.internal_add_to_all_tokens(new_token_id);
 
         self.minted_total += 1;
+        self.highest_token_id = self.highest_token_id.max(new_token_id);
 
-        // Emit the Token minted event
-        emit_event_experimental(NftEvent::NftTokenMinted(format!(
-            "Minted token {:#?} for owner {}",
-            new_token_id, to
-        )));
+        // Emit the Token minted event, a transfer from the zero address
+        emit_event_experimental(NftEvent::Transfer {
+            from: Address::from([0u8; 20]),
+            to,
+            token_id: new_token_id.into(),
+        });
 
         l1x_sdk::msg(&format!(
             "Minted token {:#?} for owner {}",
@@ -288,15 +1141,10 @@ impl NftContract {
         let mut new_token_id: u128 = self.current_token_id + 1;
 
         // Find the closed available id
-        while new_token_id <= L1X_NFT_TOTAL_SUPPLY {
-            if !self.owner_of.contains_key(&new_token_id) {
-                break;
-            }
+        while self.owner_of.contains_key(&new_token_id) {
             new_token_id += 1;
         }
 
-        assert!(new_token_id <= L1X_NFT_TOTAL_SUPPLY, "Max supply reached");
-
         self.current_token_id = new_token_id;
 
         self.mint_id_to(to, new_token_id);
@@ -304,13 +1152,59 @@ impl NftContract {
         new_token_id
     }
 
-    fn burn(&mut self, id: u128) {
-        assert_eq!(
-            caller_address(),
-            contract_owner_address(),
-            "Only the contract owner can call this method"
+    /// Lets the caller mint `count` tokens: minters always can, everybody
+    /// else is checked against the mint policy and pays the mint price.
+    fn authorize_mint(&mut self, count: u32) {
+        let caller_id = caller_address();
+        if self.roles.has_role(Role::Minter, &caller_id) {
+            return;
+        }
+
+        let payment = self.minting.authorize(
+            &caller_id,
+            count,
+            l1x_sdk::block_timestamp(),
+        );
+        if let Some((amount, payee)) = payment {
+            Self::collect_payment(amount, &payee);
+        }
+    }
+
+    /// Moves `amount` native tokens from the caller to `payee`.
+    fn collect_payment(amount: u128, payee: &Address) {
+        assert!(
+            l1x_sdk::transfer_from_caller(amount),
+            "The caller can't pay {} native tokens",
+            amount
+        );
+        assert!(
+            l1x_sdk::transfer_to(payee, amount),
+            "The payment of {} native tokens to {} failed",
+            amount,
+            payee
         );
+    }
+
+    fn burn(&mut self, id: u128) {
+        self.assert_not_paused();
+
+        // The owner, its approved spender or its operator can burn the token
+        let owner = self.owner_of(id);
+        self.assert_authorized(&caller_address(), &owner, id);
 
+        self.internal_burn(id);
+    }
+
+    fn force_burn(&mut self, id: u128) {
+        self.assert_not_paused();
+        assert!(self.force_burn_enabled, "Force burning is disabled");
+        self.roles.assert_role(Role::Burner, &caller_address());
+
+        self.internal_burn(id);
+    }
+
+    fn internal_burn(&mut self, id: u128) {
+        self.assert_token_index_complete();
         assert!(
             self.owner_of.get(&id).is_some(),
             "Token ID {:#?} Not Minted or Doesn't exist",
@@ -318,12 +1212,16 @@ impl NftContract {
         );
 
         let (from, balance_from) = self.internal_remove_token(id);
+        self.internal_remove_from_all_tokens(id);
+        self.token_uris.remove(id);
+        self.royalties.set_token(id, None);
 
-        // Emit the Token burned event
-        emit_event_experimental(NftEvent::NftTokenBurned(format!(
-            "Burn Token_ID {:#?} from Owner {} Balance {:#?}",
-            id, from, balance_from
-        )));
+        // Emit the Token burned event, a transfer to the zero address
+        emit_event_experimental(NftEvent::Transfer {
+            from,
+            to: Address::from([0u8; 20]),
+            token_id: id.into(),
+        });
 
         l1x_sdk::msg(&format!(
             "Burn Token_ID {:#?} from Owner {} Balance {:#?}",
@@ -332,6 +1230,8 @@ impl NftContract {
     }
 
     fn approve(&mut self, spender: Address, id: u128) {
+        self.assert_not_paused();
+
         // Get the caller Address
         let caller_id = l1x_sdk::caller_address();
 
@@ -363,10 +1263,11 @@ impl NftContract {
         self.get_approved.insert(id, spender.clone());
 
         // Emit the approval done event
-        emit_event_experimental(NftEvent::NftTokenApproved(format!(
-            "Approval done for token_id {:#?} from Owner {} for Spender {}",
-            id, owner.address, spender
-        )));
+        emit_event_experimental(NftEvent::Approval {
+            owner: owner.address,
+            approved: spender,
+            token_id: id.into(),
+        });
 
         l1x_sdk::msg(&format!(
             "Approval done for token_id {:#?} from Owner {} for Spender {}",
@@ -375,6 +1276,8 @@ impl NftContract {
     }
 
     fn set_approval_for_all(&mut self, operator: Address, approved: bool) {
+        self.assert_not_paused();
+
         // Get the caller Address
         let caller_id = l1x_sdk::caller_address();
 
@@ -384,17 +1287,19 @@ impl NftContract {
             approved_map.insert(operator.clone(), approved);
         } else {
             // If the entry doesn't exist, create a new map, insert the pair, and then insert the new map into `is_approved_for_all`
-            let mut new_approved_map = LookupMap::new(operator.clone().to_vec());
+            let mut new_approved_map =
+                self.internal_new_operators_map(&caller_id);
             new_approved_map.insert(operator.clone(), approved);
             self.is_approved_for_all
                 .insert(caller_id.clone(), new_approved_map);
         }
 
         // Emit the approval for All done event
-        emit_event_experimental(NftEvent::NftTokenApprovedForAll(format!(
-            "Approval-For-All done from Caller {} Operator {} Approved {:#?}",
-            caller_id, operator, approved
-        )));
+        emit_event_experimental(NftEvent::ApprovalForAll {
+            owner: caller_id,
+            operator,
+            approved,
+        });
 
         l1x_sdk::msg(&format!(
             "Approval-For-All done from Caller {} Operator {} Approved {:#?}",
@@ -403,6 +1308,8 @@ impl NftContract {
     }
 
     fn transfer_from(&mut self, from: Address, to: Address, id: u128) {
+        self.assert_not_paused();
+
         let caller_id = l1x_sdk::caller_address();
 
         // Check if the ID exists in the contract's owner_of mapping or assign default
@@ -424,36 +1331,176 @@ impl NftContract {
             from, &owner_info.address
         );
 
-        let caller_is_owner = owner_info.address == caller_id;
+        self.assert_authorized(&caller_id, &from, id);
+
+        self.internal_remove_token(id);
+        self.internal_add_token_to(to, id);
+
+        // Emit transfer done event
+        emit_event_experimental(NftEvent::Transfer {
+            from,
+            to,
+            token_id: id.into(),
+        });
+
+        l1x_sdk::msg(&format!(
+            "Token Transfer done for Token_id {:#?} From {} To {}",
+            id, from, to
+        ));
+    }
+
+    /// Panics unless `caller_id` is `owner`, the approved spender of token
+    /// `id` or an operator of `owner`.
+    fn assert_authorized(
+        &self,
+        caller_id: &Address,
+        owner: &Address,
+        id: u128,
+    ) {
+        let caller_is_owner = owner == caller_id;
         let is_approved_operator = {
             self.is_approved_for_all
-                .get(&from)
-                .and_then(|approved_map| approved_map.get(&caller_id))
+                .get(owner)
+                .and_then(|approved_map| approved_map.get(caller_id))
                 .copied()
                 .unwrap_or(false)
         };
         let is_approved_spender = {
             let spender_id = self.get_approved.get(&id);
-            spender_id == Some(&caller_id)
+            spender_id == Some(caller_id)
         };
 
         assert!(caller_is_owner || is_approved_operator || is_approved_spender,
             "Not Authorized, the caller, neither an owner, nor an approved spender, nor an approved operator,
-             CallerId: {}, Token Owner: {}, From: {}, TokenID: {}", caller_id, owner_info.address, from, id);
+             CallerId: {}, Token Owner: {}, TokenID: {}", caller_id, owner, id);
+    }
 
-        self.internal_remove_token(id);
-        self.internal_add_token_to(to, id);
+    fn call_on_received(
+        operator: &Address,
+        from: &Address,
+        to: &Address,
+        id: u128,
+        data: String,
+    ) {
+        let args = {
+            #[derive(Serialize)]
+            struct Args {
+                operator: Address,
+                from: Address,
+                id: U128,
+                data: String,
+            }
+            Args { operator: *operator, from: *from, id: id.into(), data }
+        };
 
-        // Emit transfer done event
-        emit_event_experimental(NftEvent::NftTokenTransfered(format!(
-            "Token Transfer done for Token_id {:#?} From {} To {}",
-            id, from, to
-        )));
+        let call = ContractCall {
+            contract_address: *to,
+            method_name: "nft_on_received".to_string(),
+            args: serde_json::to_vec(&args).unwrap(),
+            read_only: false,
+            fee_limit: 12,
+        };
 
-        l1x_sdk::msg(&format!(
-            "Token Transfer done for Token_id {:#?} From {} To {}",
-            id, from, to
-        ));
+        let res = l1x_sdk::call_contract(&call).unwrap_or_else(|| {
+            panic!("The receiver {} rejected the token_id {:#?}", to, id)
+        });
+        let ack = serde_json::from_slice::<String>(&res).ok();
+        assert!(
+            ack.as_deref() == Some(NFT_ON_RECEIVED_ACK),
+            "The receiver {} rejected the token_id {:#?}",
+            to,
+            id
+        );
+    }
+
+    fn token_uri(&self, id: u128) -> String {
+        // Panics if the token doesn't exist
+        self.owner_of(id);
+
+        let base_uri = self.metadata.base_uri.as_deref().unwrap_or_default();
+        match self.token_uris.get(&id) {
+            Some(uri) => format!("{}{}", base_uri, uri),
+            None if base_uri.is_empty() => String::new(),
+            None => format!("{}{}", base_uri, id),
+        }
+    }
+
+    fn set_base_uri(&mut self, base_uri: Option<String>) {
+        let caller_id = caller_address();
+        self.roles.assert_role(Role::Admin, &caller_id);
+
+        self.metadata.base_uri = base_uri;
+
+        // Emit the metadata update event for all the tokens minted so far
+        let message = format!(
+            "Base URI set to {:?} by {}",
+            self.metadata.base_uri, caller_id
+        );
+        if self.highest_token_id > 0 {
+            emit_event_experimental(NftEvent::BatchMetadataUpdate {
+                from_token_id: 1.into(),
+                to_token_id: self.highest_token_id.into(),
+            });
+        }
+
+        l1x_sdk::msg(&message);
+    }
+
+    fn set_token_uri(&mut self, id: u128, uri: Option<String>) {
+        // Panics if the token doesn't exist
+        self.owner_of(id);
+
+        match &uri {
+            Some(uri) => {
+                self.token_uris.insert(id, uri.clone());
+            }
+            None => {
+                self.token_uris.remove(id);
+            }
+        }
+
+        // Emit the metadata update event
+        let message =
+            format!("Token URI of token_id {:#?} set to {:?}", id, uri);
+        emit_event_experimental(NftEvent::MetadataUpdate {
+            token_id: id.into(),
+        });
+
+        l1x_sdk::msg(&message);
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        let caller_id = caller_address();
+        self.roles.assert_role(Role::Pauser, &caller_id);
+
+        assert_ne!(
+            self.paused, paused,
+            "The contract is already in the requested paused state: {}",
+            paused
+        );
+        self.paused = paused;
+
+        // Emit the pause state changed event
+        let message = format!("Paused set to {} by {}", paused, caller_id);
+        if paused {
+            emit_event_experimental(NftEvent::Paused { account: caller_id });
+        } else {
+            emit_event_experimental(NftEvent::Unpaused { account: caller_id });
+        }
+
+        l1x_sdk::msg(&message);
+    }
+
+    fn assert_not_paused(&self) {
+        assert!(!self.paused, "The contract is paused");
+    }
+
+    fn assert_token_index_complete(&self) {
+        assert!(
+            self.unindexed_tokens == 0,
+            "{} tokens are missing from the token index, see nft_index_tokens",
+            self.unindexed_tokens
+        );
     }
 
     fn balance_of(&self, owner: Address) -> u128 {
@@ -477,28 +1524,39 @@ impl NftContract {
         owner.unwrap().address.clone()
     }
 
-    fn owned_tokens(&self, owner: Address) -> Vec<U128> {
-        let issued_tokens = self
-            .balance_of
-            .get(&owner)
-            .unwrap_or_else(|| panic!("Not enough funds"));
-
-        let mut result = Vec::with_capacity(issued_tokens.len() as usize);
-        for idx in 0..issued_tokens.len() {
-            result.push(issued_tokens.get(idx).copied().unwrap().into())
-        }
+    fn tokens_of_owner(
+        &self,
+        owner: Address,
+        from_index: u32,
+        limit: u32,
+    ) -> Vec<U128> {
+        let issued_tokens = match self.balance_of.get(&owner) {
+            Some(issued_tokens) => issued_tokens,
+            None => return Vec::new(),
+        };
 
-        result
+        let end = from_index.saturating_add(limit).min(issued_tokens.len());
+        (from_index..end)
+            .map(|idx| issued_tokens.get(idx).copied().unwrap().into())
+            .collect()
     }
 
     fn load() -> Self {
-        match l1x_sdk::storage_read(STORAGE_CONTRACT_KEY) {
-            Some(bytes) => Self::try_from_slice(&bytes).unwrap(),
+        match state::read(STORAGE_CONTRACT_KEY) {
+            Some((version, payload)) => {
+                state::assert_not_newer(version);
+                assert!(
+                    version == STATE_VERSION,
+                    "The stored state version {} is outdated, the owner must call migrate",
+                    version
+                );
+                Self::try_from_slice(&payload).unwrap()
+            }
             None => panic!("The contract isn't initialized"),
         }
     }
 
     fn save(&mut self) {
-        l1x_sdk::storage_write(STORAGE_CONTRACT_KEY, &self.try_to_vec().unwrap());
+        state::write(STORAGE_CONTRACT_KEY, &self.try_to_vec().unwrap());
     }
 }
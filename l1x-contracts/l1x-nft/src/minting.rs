@@ -0,0 +1,163 @@
+//! Supply cap and mint policy.
+//!
+//! The max supply caps both the token ids and the number of tokens ever
+//! minted, so burning a token doesn't free its supply. Accounts with the
+//! `Minter` role can always mint, everybody else mints according to the
+//! [`MintPolicy`] and only between the optional start and end timestamps.
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk::store::LookupMap;
+use l1x_sdk::types::{Address, U128};
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+pub enum MintPolicy {
+    /// Only accounts with the `Minter` role can mint.
+    MinterOnly,
+    /// Allowlisted accounts can mint up to `per_address_limit` tokens each.
+    Allowlist { per_address_limit: Option<u32> },
+    /// Anyone can mint up to `per_address_limit` tokens each.
+    Public { per_address_limit: Option<u32> },
+    /// Anyone can mint up to `per_address_limit` tokens each for `price`
+    /// native tokens per token, which are forwarded to `payee`.
+    Paid { price: U128, payee: Address, per_address_limit: Option<u32> },
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct Minting {
+    /// `None` if the supply is unlimited
+    pub max_supply: Option<u128>,
+    pub policy: MintPolicy,
+    /// Timestamps are in `l1x_sdk::block_timestamp` units
+    pub start: Option<u64>,
+    pub end: Option<u64>,
+    allowlist: LookupMap<Address, bool>,
+    /// Tokens minted by every account under the mint policy
+    minted_by: LookupMap<Address, u32>,
+}
+
+impl Minting {
+    pub fn new(
+        max_supply: Option<u128>,
+        policy: MintPolicy,
+        allowlist_prefix: Vec<u8>,
+        minted_by_prefix: Vec<u8>,
+    ) -> Self {
+        Self {
+            max_supply,
+            policy,
+            start: None,
+            end: None,
+            allowlist: LookupMap::new(allowlist_prefix),
+            minted_by: LookupMap::new(minted_by_prefix),
+        }
+    }
+
+    /// Panics if token `id` can't be minted after `minted_total` tokens.
+    pub fn assert_within_max_supply(&self, id: u128, minted_total: u64) {
+        if let Some(max_supply) = self.max_supply {
+            assert!(
+                id <= max_supply && u128::from(minted_total) < max_supply,
+                "Max supply reached"
+            );
+        }
+    }
+
+    pub fn remaining_supply(&self, minted_total: u64) -> Option<u128> {
+        self.max_supply
+            .map(|max_supply| max_supply.saturating_sub(minted_total.into()))
+    }
+
+    pub fn set_policy(&mut self, policy: MintPolicy) {
+        if let MintPolicy::Paid { price, .. } = &policy {
+            assert!(price.0 > 0, "The mint price must be positive");
+        }
+        self.policy = policy;
+    }
+
+    pub fn set_window(&mut self, start: Option<u64>, end: Option<u64>) {
+        if let (Some(start), Some(end)) = (start, end) {
+            assert!(start < end, "The mint start must be before its end");
+        }
+        self.start = start;
+        self.end = end;
+    }
+
+    pub fn is_allowlisted(&self, account_id: &Address) -> bool {
+        self.allowlist.get(account_id).copied().unwrap_or_default()
+    }
+
+    pub fn set_allowlisted(&mut self, account_id: Address, allowlisted: bool) {
+        if allowlisted {
+            self.allowlist.insert(account_id, true);
+        } else {
+            self.allowlist.remove(account_id);
+        }
+    }
+
+    pub fn minted_by(&self, account_id: &Address) -> u32 {
+        self.minted_by.get(account_id).copied().unwrap_or_default()
+    }
+
+    /// Checks that `minter` can mint `count` tokens at `timestamp` under the
+    /// mint policy and counts them. Returns the price to pay and its payee.
+    pub fn authorize(
+        &mut self,
+        minter: &Address,
+        count: u32,
+        timestamp: u64,
+    ) -> Option<(u128, Address)> {
+        if let Some(start) = self.start {
+            assert!(timestamp >= start, "The mint hasn't started yet");
+        }
+        if let Some(end) = self.end {
+            assert!(timestamp < end, "The mint is over");
+        }
+
+        let (per_address_limit, payment) = match &self.policy {
+            MintPolicy::MinterOnly => {
+                panic!("{} is missing role Minter", minter)
+            }
+            MintPolicy::Allowlist { per_address_limit } => {
+                assert!(
+                    self.is_allowlisted(minter),
+                    "{} is not allowlisted to mint",
+                    minter
+                );
+                (*per_address_limit, None)
+            }
+            MintPolicy::Public { per_address_limit } => {
+                (*per_address_limit, None)
+            }
+            MintPolicy::Paid { price, payee, per_address_limit } => {
+                let amount = price
+                    .0
+                    .checked_mul(count.into())
+                    .expect("The mint price is overflowed");
+                (*per_address_limit, Some((amount, *payee)))
+            }
+        };
+
+        let minted = self
+            .minted_by(minter)
+            .checked_add(count)
+            .expect("The minted count is overflowed");
+        if let Some(per_address_limit) = per_address_limit {
+            assert!(
+                minted <= per_address_limit,
+                "The mint limit of {} tokens per address is reached",
+                per_address_limit
+            );
+        }
+        self.minted_by.insert(*minter, minted);
+
+        payment
+    }
+}
@@ -0,0 +1,122 @@
+//! ERC-2981 style royalties on secondary sales.
+//!
+//! A royalty of `sale_price * bps / 10_000` is due to `receiver` on every sale
+//! of a token. Marketplaces that pay the recipients directly can use the
+//! optional splits, which share the royalty between several accounts. Every
+//! token pays the default royalty unless it has its own.
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_bps::{bps_of, MAX_BPS};
+use l1x_sdk::store::LookupMap;
+use l1x_sdk::types::Address;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+pub struct Royalty {
+    /// Receiver of the whole royalty reported by `nft_royalty_info`
+    pub receiver: Address,
+    /// Royalty in basis points of the sale price
+    pub bps: u16,
+    /// Shares of the royalty in basis points of the royalty, adding up to
+    /// 10_000. `receiver` gets the whole royalty if there are none.
+    pub splits: Vec<RoyaltySplit>,
+}
+
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+)]
+pub struct RoyaltySplit {
+    pub receiver: Address,
+    pub bps: u16,
+}
+
+impl Royalty {
+    pub fn assert_valid(&self) {
+        assert!(self.bps <= MAX_BPS, "Royalty bps can't exceed {}", MAX_BPS);
+        if !self.splits.is_empty() {
+            let total: u32 =
+                self.splits.iter().map(|split| u32::from(split.bps)).sum();
+            assert!(
+                total == u32::from(MAX_BPS),
+                "Royalty splits must add up to {} bps",
+                MAX_BPS
+            );
+        }
+    }
+
+    pub fn amount(&self, sale_price: u128) -> u128 {
+        bps_of(sale_price, self.bps)
+    }
+
+    /// Returns the share of the royalty on `sale_price` of every recipient.
+    /// The rounding dust goes to the first one.
+    pub fn split_amounts(&self, sale_price: u128) -> Vec<(Address, u128)> {
+        let amount = self.amount(sale_price);
+        if self.splits.is_empty() {
+            return vec![(self.receiver, amount)];
+        }
+
+        let mut shares: Vec<(Address, u128)> = self
+            .splits
+            .iter()
+            .map(|split| (split.receiver, bps_of(amount, split.bps)))
+            .collect();
+        let distributed: u128 = shares.iter().map(|(_, share)| share).sum();
+        shares[0].1 += amount - distributed;
+
+        shares
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct Royalties {
+    pub default: Option<Royalty>,
+    per_token: LookupMap<u128, Royalty>,
+}
+
+impl Royalties {
+    pub fn new(default: Option<Royalty>, per_token_prefix: Vec<u8>) -> Self {
+        if let Some(royalty) = &default {
+            royalty.assert_valid();
+        }
+
+        Self { default, per_token: LookupMap::new(per_token_prefix) }
+    }
+
+    /// Returns the royalty of token `id`.
+    pub fn of(&self, id: u128) -> Option<&Royalty> {
+        self.per_token.get(&id).or(self.default.as_ref())
+    }
+
+    pub fn set_default(&mut self, royalty: Option<Royalty>) {
+        if let Some(royalty) = &royalty {
+            royalty.assert_valid();
+        }
+        self.default = royalty;
+    }
+
+    pub fn set_token(&mut self, id: u128, royalty: Option<Royalty>) {
+        match royalty {
+            Some(royalty) => {
+                royalty.assert_valid();
+                self.per_token.insert(id, royalty);
+            }
+            None => {
+                self.per_token.remove(id);
+            }
+        }
+    }
+}
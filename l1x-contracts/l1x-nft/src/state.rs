@@ -0,0 +1,132 @@
+//! Versioned storage of the contract state.
+//!
+//! The state is stored in the versioned envelope of `l1x-versioned-state`,
+//! where the payload is the Borsh encoding of the layout of that version.
+//! Instances deployed before versioning store the bare payload of layout 0,
+//! which starts with the collection name and reads as version 0, see
+//! `l1x_versioned_state`.
+//!
+//! Every change of the layout bumps [`STATE_VERSION`] and adds a step to
+//! [`upgrade`] that converts the payload of the previous version. New fields
+//! are only appended at the end of [`NftContract`], so a step just appends
+//! the encoding of their initial values.
+use borsh::{BorshDeserialize, BorshSerialize};
+use l1x_sdk::store::{LookupMap, Vector};
+use l1x_sdk::types::Address;
+
+use crate::{MintPolicy, NFTMetadata, NftContract, OwnerInfo};
+
+pub(crate) use l1x_versioned_state::read;
+
+/// Version of the layout written by this code.
+pub const STATE_VERSION: u16 = 1;
+
+pub(crate) fn write(key: &[u8], payload: &[u8]) {
+    l1x_versioned_state::write(key, STATE_VERSION, payload);
+}
+
+pub(crate) fn assert_not_newer(version: u16) {
+    l1x_versioned_state::assert_not_newer(version, STATE_VERSION);
+}
+
+/// Converts a payload of layout `version` to the current layout, one version
+/// at a time.
+pub(crate) fn upgrade(mut version: u16, mut payload: Vec<u8>) -> NftContract {
+    assert_not_newer(version);
+    let from_version = version;
+
+    while version < STATE_VERSION {
+        payload = match version {
+            0 => v0_to_v1(payload),
+            _ => {
+                unreachable!("No migration from the state version {}", version)
+            }
+        };
+        version += 1;
+    }
+
+    let mut contract = NftContract::try_from_slice(&payload).unwrap();
+    if from_version < 1 {
+        // The global token index of layout 1 starts empty. Layout 0 counted
+        // every mint, burned tokens included, and the tokens the index misses
+        // raise the highest id as they are indexed.
+        contract.unindexed_tokens = contract.minted_total;
+        contract.highest_token_id = contract.current_token_id;
+    }
+
+    contract
+}
+
+/// Layout 0: the collection before roles, base URIs and minting policies
+/// were added.
+#[derive(BorshDeserialize)]
+struct NFTMetadataV0 {
+    name: String,
+    decimals: u8,
+    symbol: String,
+    icon: Option<String>,
+}
+
+#[derive(BorshDeserialize)]
+struct NftContractV0 {
+    metadata: NFTMetadataV0,
+    current_token_id: u128,
+    minted_total: u64,
+    balance_of: LookupMap<Address, Vector<u128>>,
+    owner_of: LookupMap<u128, OwnerInfo>,
+    get_approved: LookupMap<u128, Address>,
+    /// The operator maps were keyed by the first operator an owner approved,
+    /// so owners approving the same operator shared their approvals
+    _is_approved_for_all: LookupMap<Address, LookupMap<Address, bool>>,
+}
+
+/// Supply cap of the instances deployed before it was configurable.
+const LEGACY_MAX_SUPPLY: u128 = 10_000;
+
+/// Layout 1 adds `base_uri` to the metadata and appends the fields from
+/// `roles` on, with the owner granted the roles `new` grants. The tokens,
+/// balances and token approvals live under the same storage keys, while the
+/// operators start empty and owners approve them again. Anyone could
+/// mint before and burners could burn any token, so the instance keeps a
+/// public mint with the former supply cap and force burning enabled. The
+/// existing tokens are added to the global token index by
+/// `nft_index_tokens` and `nft_index_burned_tokens`.
+fn v0_to_v1(payload: Vec<u8>) -> Vec<u8> {
+    let old = NftContractV0::try_from_slice(&payload).unwrap();
+    let metadata = NFTMetadata {
+        name: old.metadata.name,
+        decimals: old.metadata.decimals,
+        symbol: old.metadata.symbol,
+        icon: old.metadata.icon,
+        base_uri: None,
+    };
+
+    let mut initial =
+        NftContract::with_metadata(metadata, Some(LEGACY_MAX_SUPPLY));
+    NftContract::grant_owner_roles(&mut initial.roles);
+    initial.minting.set_policy(MintPolicy::Public { per_address_limit: None });
+    initial.force_burn_enabled = true;
+
+    [
+        initial.metadata.try_to_vec().unwrap(),
+        old.current_token_id.try_to_vec().unwrap(),
+        old.minted_total.try_to_vec().unwrap(),
+        old.balance_of.try_to_vec().unwrap(),
+        old.owner_of.try_to_vec().unwrap(),
+        old.get_approved.try_to_vec().unwrap(),
+        initial.is_approved_for_all.try_to_vec().unwrap(),
+        initial.roles.try_to_vec().unwrap(),
+        initial.paused.try_to_vec().unwrap(),
+        initial.token_uris.try_to_vec().unwrap(),
+        initial.minting.try_to_vec().unwrap(),
+        initial.all_tokens.try_to_vec().unwrap(),
+        initial.all_tokens_index.try_to_vec().unwrap(),
+        initial.royalties.try_to_vec().unwrap(),
+        initial.force_burn_enabled.try_to_vec().unwrap(),
+        initial.vouchers.try_to_vec().unwrap(),
+        initial.unindexed_tokens.try_to_vec().unwrap(),
+        initial.last_unindexed_burn.try_to_vec().unwrap(),
+        initial.highest_token_id.try_to_vec().unwrap(),
+    ]
+    .concat()
+}
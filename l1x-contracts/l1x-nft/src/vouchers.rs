@@ -0,0 +1,82 @@
+//! Signed mint vouchers for lazy minting.
+//!
+//! The voucher signer signs a [`MintVoucher`] in the `l1x_eip712` domain and
+//! the token only exists once a buyer redeems the voucher with
+//! `nft_redeem_voucher`. Every voucher can be redeemed once.
+use borsh::{BorshDeserialize, BorshSerialize};
+use ethers::abi::{encode, Token};
+use ethers::types::U256;
+use ethers::utils::keccak256;
+use l1x_eip712::{recover_signer, to_evm_address, typed_data_digest};
+use l1x_sdk::store::LookupMap;
+use l1x_sdk::types::{Address, U128, U64};
+use serde::{Deserialize, Serialize};
+
+const MINT_VOUCHER_TYPE: &str =
+    "MintVoucher(uint256 tokenId,string uri,uint256 minPrice,uint256 expiry,address recipient)";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MintVoucher {
+    pub token_id: U128,
+    /// Token URI, none if empty
+    pub uri: String,
+    /// Native tokens the redeemer pays to the signer
+    pub min_price: U128,
+    /// Last `l1x_sdk::block_timestamp` the voucher is valid at
+    pub expiry: U64,
+    pub recipient: Address,
+}
+
+impl MintVoucher {
+    /// Returns the EIP-712 digest signed by the voucher signer.
+    pub fn digest(
+        &self,
+        collection_name: &str,
+        verifying_contract: &Address,
+    ) -> [u8; 32] {
+        let struct_hash = keccak256(encode(&[
+            Token::FixedBytes(keccak256(MINT_VOUCHER_TYPE).to_vec()),
+            Token::Uint(U256::from(self.token_id.0)),
+            Token::FixedBytes(keccak256(&self.uri).to_vec()),
+            Token::Uint(U256::from(self.min_price.0)),
+            Token::Uint(U256::from(self.expiry.0)),
+            Token::Address(to_evm_address(&self.recipient)),
+        ]));
+
+        typed_data_digest(collection_name, verifying_contract, struct_hash)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct Vouchers {
+    /// `None` if vouchers can't be redeemed
+    pub signer: Option<Address>,
+    /// Digests of the redeemed vouchers
+    redeemed: LookupMap<[u8; 32], bool>,
+}
+
+impl Vouchers {
+    pub fn new(redeemed_prefix: Vec<u8>) -> Self {
+        Self { signer: None, redeemed: LookupMap::new(redeemed_prefix) }
+    }
+
+    pub fn is_redeemed(&self, digest: &[u8; 32]) -> bool {
+        self.redeemed.get(digest).copied().unwrap_or_default()
+    }
+
+    /// Checks that the voucher with `digest` is signed by the voucher signer
+    /// and not redeemed yet, then marks it redeemed. Returns the signer.
+    pub fn redeem(&mut self, digest: [u8; 32], signature: &str) -> Address {
+        let signer = self.signer.expect("Vouchers aren't enabled");
+        assert!(!self.is_redeemed(&digest), "The voucher is already redeemed");
+        assert_eq!(
+            recover_signer(digest, signature),
+            signer,
+            "Invalid voucher signature"
+        );
+
+        self.redeemed.insert(digest, true);
+
+        signer
+    }
+}
@@ -0,0 +1,1614 @@
+use borsh::BorshSerialize;
+use ethers::signers::LocalWallet;
+use ethers::types::H256;
+use l1x_mock_host as host;
+use l1x_nft::{
+    MintPolicy, MintVoucher, NFTMetadata, NftContract, NftEvent, Role, Royalty,
+    RoyaltySplit, STATE_VERSION,
+};
+use l1x_sdk::store::{LookupMap, Vector};
+use l1x_sdk::types::{Address, U128, U64};
+
+fn metadata() -> NFTMetadata {
+    serde_json::from_value(serde_json::json!({
+        "name": "L1X NFT",
+        "decimals": 0,
+        "symbol": "LNFT",
+        "icon": null,
+    }))
+    .unwrap()
+}
+
+fn alice() -> Address {
+    host::address(1)
+}
+
+fn bob() -> Address {
+    host::address(2)
+}
+
+fn carol() -> Address {
+    host::address(3)
+}
+
+fn zero_address() -> Address {
+    Address::from([0u8; 20])
+}
+
+fn setup() {
+    setup_with_max_supply(Some(10_000));
+}
+
+fn setup_with_max_supply(max_supply: Option<u128>) {
+    host::set_caller(host::contract_owner());
+    NftContract::new(metadata(), max_supply.map(U128), None);
+}
+
+/// Burns token `id` as its holder, then gives the control back to the
+/// contract owner.
+fn burn_as_holder(id: u128) {
+    host::set_caller(NftContract::nft_owner_of(U128(id)));
+    NftContract::nft_burn(U128(id));
+    host::set_caller(host::contract_owner());
+}
+
+fn owned(owner: Address) -> Vec<u128> {
+    let mut ids: Vec<u128> = NftContract::nft_owned_tokens(owner)
+        .into_iter()
+        .map(|id| id.0)
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn metadata_views() {
+    setup();
+
+    assert_eq!(NftContract::nft_name(), "L1X NFT");
+    assert_eq!(NftContract::nft_symbol(), "LNFT");
+    assert_eq!(NftContract::nft_decimals(), 0);
+    assert_eq!(NftContract::nft_minted_total().0, 0);
+}
+
+#[test]
+#[should_panic(expected = "Only the contract owner can call this method")]
+fn new_rejects_non_owner() {
+    host::set_caller(alice());
+    NftContract::new(metadata(), None, None);
+}
+
+#[test]
+fn mint_to_assigns_sequential_ids() {
+    setup();
+
+    assert_eq!(NftContract::nft_mint_to(alice()).0, 1);
+    assert_eq!(NftContract::nft_mint_to(bob()).0, 2);
+    assert_eq!(NftContract::nft_mint_to(alice()).0, 3);
+
+    assert_eq!(NftContract::nft_minted_total().0, 3);
+    assert_eq!(NftContract::nft_balance_of(alice()).0, 2);
+    assert_eq!(NftContract::nft_owner_of(U128(2)), bob());
+    assert_eq!(owned(alice()), vec![1, 3]);
+}
+
+#[test]
+fn mint_to_skips_taken_ids() {
+    setup();
+
+    NftContract::nft_mint_id_to(alice(), U128(1));
+    NftContract::nft_mint_id_to(alice(), U128(2));
+
+    assert_eq!(NftContract::nft_mint_to(bob()).0, 3);
+}
+
+#[test]
+#[should_panic(expected = "Max supply reached")]
+fn mint_id_to_enforces_cap() {
+    setup();
+
+    NftContract::nft_mint_id_to(alice(), U128(10_001));
+}
+
+#[test]
+fn holder_can_burn() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(alice());
+    host::clear_captures();
+
+    host::set_caller(alice());
+    NftContract::nft_burn(U128(1));
+
+    assert_eq!(
+        host::decoded_events::<NftEvent>(),
+        vec![NftEvent::Transfer {
+            from: alice(),
+            to: zero_address(),
+            token_id: U128(1),
+        }]
+    );
+
+    assert_eq!(owned(alice()), vec![2]);
+    assert_eq!(NftContract::nft_balance_of(alice()).0, 1);
+}
+
+#[test]
+fn approved_spender_and_operator_can_burn() {
+    setup();
+    NftContract::nft_batch_mint(alice(), 2);
+
+    host::set_caller(alice());
+    NftContract::nft_approve(bob(), U128(1));
+    NftContract::nft_set_approval_for_all(carol(), true);
+    host::set_caller(bob());
+    NftContract::nft_burn(U128(1));
+    host::set_caller(carol());
+    NftContract::nft_burn(U128(2));
+
+    assert_eq!(NftContract::nft_balance_of(alice()).0, 0);
+}
+
+#[test]
+fn operators_are_kept_per_owner() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    host::set_caller(alice());
+    NftContract::nft_set_approval_for_all(bob(), true);
+    host::set_caller(carol());
+    NftContract::nft_set_approval_for_all(bob(), true);
+    NftContract::nft_set_approval_for_all(bob(), false);
+
+    host::set_caller(bob());
+    NftContract::nft_transfer_from(alice(), carol(), U128(1));
+
+    assert_eq!(NftContract::nft_owner_of(U128(1)), carol());
+}
+
+#[test]
+#[should_panic(expected = "Not Authorized")]
+fn stranger_cannot_burn() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    host::set_caller(bob());
+    NftContract::nft_burn(U128(1));
+}
+
+#[test]
+#[should_panic(expected = "Not Authorized")]
+fn contract_owner_cannot_burn_held_tokens() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    NftContract::nft_burn(U128(1));
+}
+
+#[test]
+fn owner_can_transfer() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(alice());
+
+    host::set_caller(alice());
+    NftContract::nft_transfer_from(alice(), bob(), U128(1));
+
+    assert_eq!(NftContract::nft_owner_of(U128(1)), bob());
+    assert_eq!(owned(alice()), vec![2, 3]);
+    assert_eq!(owned(bob()), vec![1]);
+}
+
+#[test]
+fn approved_spender_can_transfer_once() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    host::set_caller(alice());
+    NftContract::nft_approve(bob(), U128(1));
+
+    host::set_caller(bob());
+    NftContract::nft_transfer_from(alice(), carol(), U128(1));
+
+    assert_eq!(NftContract::nft_owner_of(U128(1)), carol());
+}
+
+#[test]
+fn operator_can_transfer() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    host::set_caller(alice());
+    NftContract::nft_set_approval_for_all(bob(), true);
+
+    host::set_caller(bob());
+    NftContract::nft_transfer_from(alice(), carol(), U128(1));
+
+    assert_eq!(NftContract::nft_owner_of(U128(1)), carol());
+}
+
+#[test]
+#[should_panic(expected = "Not Authorized")]
+fn stranger_cannot_transfer() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    host::set_caller(bob());
+    NftContract::nft_transfer_from(alice(), bob(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "is not Owner and is not an authorized operator")]
+fn stranger_cannot_approve() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    host::set_caller(bob());
+    NftContract::nft_approve(bob(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "is not minted or doesn't exist")]
+fn owner_of_unknown_token_panics() {
+    setup();
+
+    NftContract::nft_owner_of(U128(42));
+}
+
+#[test]
+fn owner_starts_with_all_roles() {
+    setup();
+    let owner = host::contract_owner();
+
+    assert!(NftContract::nft_has_role(Role::Admin, owner));
+    assert!(NftContract::nft_has_role(Role::Minter, owner));
+    assert!(NftContract::nft_has_role(Role::Burner, owner));
+    assert!(NftContract::nft_has_role(Role::Pauser, owner));
+    assert!(!NftContract::nft_has_role(Role::Pauser, alice()));
+}
+
+#[test]
+fn role_changes_emit_events() {
+    setup();
+    host::clear_captures();
+    let owner = host::contract_owner();
+
+    NftContract::nft_grant_role(Role::Burner, bob());
+    NftContract::nft_revoke_role(Role::Burner, bob());
+    NftContract::nft_renounce_role(Role::Pauser);
+
+    assert_eq!(
+        host::decoded_events::<NftEvent>(),
+        vec![
+            NftEvent::RoleGranted {
+                role: Role::Burner,
+                account: bob(),
+                sender: owner,
+            },
+            NftEvent::RoleRevoked {
+                role: Role::Burner,
+                account: bob(),
+                sender: owner,
+            },
+            NftEvent::RoleRevoked {
+                role: Role::Pauser,
+                account: owner,
+                sender: owner,
+            },
+        ]
+    );
+}
+
+#[test]
+fn granted_burner_can_force_burn() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_grant_role(Role::Burner, bob());
+    NftContract::nft_set_force_burn_enabled(true);
+
+    host::set_caller(bob());
+    NftContract::nft_force_burn(U128(1));
+
+    assert_eq!(NftContract::nft_balance_of(alice()).0, 0);
+}
+
+#[test]
+#[should_panic(expected = "Force burning is disabled")]
+fn force_burn_is_disabled_by_default() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    assert!(!NftContract::nft_force_burn_enabled());
+
+    NftContract::nft_force_burn(U128(1));
+}
+
+#[test]
+#[should_panic(expected = "is missing role Burner")]
+fn renounced_burner_cannot_force_burn() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_set_force_burn_enabled(true);
+    NftContract::nft_renounce_role(Role::Burner);
+
+    NftContract::nft_force_burn(U128(1));
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_enable_force_burn() {
+    setup();
+
+    host::set_caller(alice());
+    NftContract::nft_set_force_burn_enabled(true);
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_revoke_roles() {
+    setup();
+
+    host::set_caller(alice());
+    NftContract::nft_revoke_role(Role::Burner, host::contract_owner());
+}
+
+#[test]
+fn pause_blocks_until_unpaused() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    NftContract::nft_pause();
+    assert!(NftContract::nft_is_paused());
+    NftContract::nft_unpause();
+
+    host::set_caller(alice());
+    NftContract::nft_transfer_from(alice(), bob(), U128(1));
+    assert_eq!(NftContract::nft_owner_of(U128(1)), bob());
+}
+
+#[test]
+#[should_panic(expected = "The contract is paused")]
+fn paused_contract_rejects_transfer() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_pause();
+
+    host::set_caller(alice());
+    NftContract::nft_transfer_from(alice(), bob(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "The contract is paused")]
+fn paused_contract_rejects_mint() {
+    setup();
+    NftContract::nft_pause();
+
+    NftContract::nft_mint_to(alice());
+}
+
+#[test]
+#[should_panic(expected = "is missing role Pauser")]
+fn non_pauser_cannot_pause() {
+    setup();
+
+    host::set_caller(alice());
+    NftContract::nft_pause();
+}
+
+#[test]
+#[should_panic(expected = "already in the requested paused state")]
+fn unpause_requires_paused_contract() {
+    setup();
+
+    NftContract::nft_unpause();
+}
+
+/// Layout 0 of the collection, as written before the state was versioned.
+#[derive(BorshSerialize)]
+struct LegacyMetadata {
+    name: String,
+    decimals: u8,
+    symbol: String,
+    icon: Option<String>,
+}
+
+#[derive(BorshSerialize)]
+struct LegacyOwnerInfo {
+    address: Address,
+    token_idx: u32,
+}
+
+#[derive(BorshSerialize)]
+struct LegacyNft {
+    metadata: LegacyMetadata,
+    current_token_id: u128,
+    minted_total: u64,
+    balance_of: LookupMap<Address, Vector<u128>>,
+    owner_of: LookupMap<u128, LegacyOwnerInfo>,
+    get_approved: LookupMap<u128, Address>,
+    is_approved_for_all: LookupMap<Address, LookupMap<Address, bool>>,
+}
+
+/// Stores a layout 0 collection where alice owns token 1, token 2 is burned
+/// and bob owns token 500, minted by id. Alice approved carol as operator.
+fn deploy_legacy() {
+    let mut alice_tokens =
+        Vector::new([&alice().to_vec(), b"ids".as_slice()].concat());
+    alice_tokens.push(1u128);
+    let mut bob_tokens =
+        Vector::new([&bob().to_vec(), b"ids".as_slice()].concat());
+    bob_tokens.push(500u128);
+
+    let mut legacy = LegacyNft {
+        metadata: LegacyMetadata {
+            name: "Legacy".to_string(),
+            decimals: 0,
+            symbol: "LGC".to_string(),
+            icon: None,
+        },
+        current_token_id: 2,
+        minted_total: 3,
+        balance_of: LookupMap::new(b"balances".to_vec()),
+        owner_of: LookupMap::new(b"owners".to_vec()),
+        get_approved: LookupMap::new(b"approved".to_vec()),
+        is_approved_for_all: LookupMap::new(b"approved-all".to_vec()),
+    };
+    legacy.balance_of.insert(alice(), alice_tokens);
+    legacy.balance_of.insert(bob(), bob_tokens);
+    legacy
+        .owner_of
+        .insert(1, LegacyOwnerInfo { address: alice(), token_idx: 0 });
+    legacy
+        .owner_of
+        .insert(500, LegacyOwnerInfo { address: bob(), token_idx: 0 });
+    let mut alice_operators = LookupMap::new(carol().to_vec());
+    alice_operators.insert(carol(), true);
+    legacy.is_approved_for_all.insert(alice(), alice_operators);
+
+    l1x_sdk::storage_write(b"state", &legacy.try_to_vec().unwrap());
+}
+
+#[test]
+fn new_writes_the_current_state_version() {
+    setup();
+    assert_eq!(NftContract::nft_state_version(), STATE_VERSION);
+}
+
+#[test]
+#[should_panic(
+    expected = "The stored state version 0 is outdated, the owner must call migrate"
+)]
+fn legacy_state_requires_migration() {
+    deploy_legacy();
+    NftContract::nft_name();
+}
+
+#[test]
+fn migrate_upgrades_legacy_state() {
+    deploy_legacy();
+
+    host::set_caller(host::contract_owner());
+    NftContract::migrate();
+
+    assert_eq!(NftContract::nft_state_version(), STATE_VERSION);
+    assert_eq!(NftContract::nft_name(), "Legacy");
+    assert_eq!(NftContract::nft_owner_of(U128(1)), alice());
+    assert_eq!(owned(alice()), vec![1]);
+    assert!(NftContract::nft_has_role(Role::Minter, host::contract_owner()));
+    assert_eq!(NftContract::nft_token_uri(U128(1)), "");
+    assert_eq!(NftContract::nft_max_supply(), Some(U128(10_000)));
+    assert_eq!(NftContract::nft_remaining_supply(), Some(U128(9_997)));
+    assert_eq!(NftContract::nft_unindexed_tokens().0, 3);
+    NftContract::nft_index_tokens(vec![alice(), bob()]);
+    NftContract::nft_index_burned_tokens(vec![U128(2)]);
+    assert_eq!(NftContract::nft_unindexed_tokens().0, 0);
+    assert_eq!(NftContract::nft_total_supply().0, 2);
+    assert_eq!(NftContract::nft_token_by_index(0).0, 1);
+    assert_eq!(NftContract::nft_token_by_index(1).0, 500);
+    host::clear_captures();
+    NftContract::nft_set_base_uri(Some("ipfs://c/".to_string()));
+    assert_eq!(
+        host::decoded_events::<NftEvent>(),
+        vec![NftEvent::BatchMetadataUpdate {
+            from_token_id: U128(1),
+            to_token_id: U128(500),
+        }]
+    );
+    assert_eq!(NftContract::nft_default_royalty(), None);
+    assert!(NftContract::nft_force_burn_enabled());
+    assert_eq!(NftContract::nft_voucher_signer(), None);
+    assert_eq!(
+        NftContract::nft_mint_policy(),
+        MintPolicy::Public { per_address_limit: None }
+    );
+
+    assert_eq!(NftContract::nft_mint_to(bob()).0, 3);
+    host::set_caller(alice());
+    NftContract::nft_transfer_from(alice(), carol(), U128(1));
+    assert_eq!(owned(carol()), vec![1]);
+
+    // Anyone could mint before the migration
+    assert_eq!(NftContract::nft_mint_to(alice()).0, 4);
+}
+
+/// Migrates the collection of `deploy_legacy` as the contract owner.
+fn migrate_legacy() {
+    deploy_legacy();
+    host::set_caller(host::contract_owner());
+    NftContract::migrate();
+}
+
+#[test]
+#[should_panic(expected = "Not Authorized")]
+fn migrate_drops_the_legacy_operators() {
+    migrate_legacy();
+
+    host::set_caller(carol());
+    NftContract::nft_transfer_from(alice(), carol(), U128(1));
+}
+
+#[test]
+#[should_panic(
+    expected = "1 tokens are missing from the token index, see nft_index_tokens"
+)]
+fn enumeration_waits_for_the_migrated_tokens() {
+    migrate_legacy();
+
+    // The burned token isn't reported
+    NftContract::nft_index_tokens(vec![alice(), bob()]);
+    NftContract::nft_total_supply();
+}
+
+#[test]
+#[should_panic(
+    expected = "2 tokens are missing from the token index, see nft_index_tokens"
+)]
+fn burns_wait_for_the_migrated_tokens() {
+    migrate_legacy();
+    NftContract::nft_index_tokens(vec![alice()]);
+
+    burn_as_holder(1);
+}
+
+#[test]
+#[should_panic(
+    expected = "3 tokens are missing from the token index, see nft_index_tokens"
+)]
+fn mints_wait_for_the_migrated_tokens() {
+    migrate_legacy();
+
+    NftContract::nft_mint_id_to(carol(), U128(2));
+}
+
+#[test]
+fn index_tokens_skips_indexed_tokens() {
+    migrate_legacy();
+
+    NftContract::nft_index_tokens(vec![bob()]);
+    NftContract::nft_index_tokens(vec![alice(), bob(), carol()]);
+    assert_eq!(NftContract::nft_unindexed_tokens().0, 1);
+    NftContract::nft_index_burned_tokens(vec![U128(2)]);
+
+    assert_eq!(NftContract::nft_total_supply().0, 2);
+    let mut ids: Vec<u128> =
+        (0..2).map(|index| NftContract::nft_token_by_index(index).0).collect();
+    ids.sort();
+    assert_eq!(ids, vec![1, 500]);
+}
+
+#[test]
+#[should_panic(expected = "Token 500 isn't burned")]
+fn index_burned_tokens_rejects_owned_tokens() {
+    migrate_legacy();
+
+    NftContract::nft_index_burned_tokens(vec![U128(2), U128(500)]);
+}
+
+#[test]
+#[should_panic(
+    expected = "The burned token 2 is out of order or already reported"
+)]
+fn index_burned_tokens_counts_every_burn_once() {
+    migrate_legacy();
+
+    NftContract::nft_index_burned_tokens(vec![U128(2)]);
+    NftContract::nft_index_burned_tokens(vec![U128(2)]);
+}
+
+#[test]
+#[should_panic(expected = "The unindexed tokens are underflowed")]
+fn index_burned_tokens_cannot_overcount() {
+    migrate_legacy();
+    NftContract::nft_index_tokens(vec![alice(), bob()]);
+
+    NftContract::nft_index_burned_tokens(vec![U128(2), U128(3)]);
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_index_tokens() {
+    migrate_legacy();
+
+    host::set_caller(alice());
+    NftContract::nft_index_tokens(vec![alice()]);
+}
+
+#[test]
+#[should_panic(expected = "Only the contract owner can call this method")]
+fn non_owner_cannot_migrate() {
+    deploy_legacy();
+
+    host::set_caller(alice());
+    NftContract::migrate();
+}
+
+#[test]
+#[should_panic(
+    expected = "The stored state version 2 is newer than the supported version 1"
+)]
+fn newer_state_is_rejected() {
+    setup();
+    let mut bytes = host::storage_get(b"state").unwrap();
+    bytes[4..6].copy_from_slice(&(STATE_VERSION + 1).to_le_bytes());
+    l1x_sdk::storage_write(b"state", &bytes);
+
+    NftContract::nft_name();
+}
+
+#[test]
+fn token_uri_is_empty_without_uris() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    assert_eq!(NftContract::nft_token_uri(U128(1)), "");
+}
+
+#[test]
+fn token_uri_defaults_to_base_uri_and_id() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_set_base_uri(Some("ipfs://collection/".to_string()));
+
+    assert_eq!(NftContract::nft_token_uri(U128(1)), "ipfs://collection/1");
+    let metadata = serde_json::to_value(NftContract::nft_metadata()).unwrap();
+    assert_eq!(metadata["base_uri"], "ipfs://collection/");
+}
+
+#[test]
+fn token_uri_appends_per_token_uri() {
+    setup();
+    NftContract::nft_mint_to_with_uri(alice(), "ipfs://token-1".to_string());
+    assert_eq!(NftContract::nft_token_uri(U128(1)), "ipfs://token-1");
+
+    NftContract::nft_set_base_uri(Some("https://example.com/".to_string()));
+    NftContract::nft_set_token_uri(U128(1), Some("one.json".to_string()));
+    assert_eq!(
+        NftContract::nft_token_uri(U128(1)),
+        "https://example.com/one.json"
+    );
+
+    NftContract::nft_set_token_uri(U128(1), None);
+    assert_eq!(NftContract::nft_token_uri(U128(1)), "https://example.com/1");
+}
+
+#[test]
+fn burn_clears_token_uri() {
+    setup();
+    NftContract::nft_mint_to_with_uri(alice(), "ipfs://token-1".to_string());
+    burn_as_holder(1);
+    NftContract::nft_mint_id_to(alice(), U128(1));
+
+    assert_eq!(NftContract::nft_token_uri(U128(1)), "");
+}
+
+#[test]
+#[should_panic(expected = "is not minted or doesn't exist")]
+fn token_uri_requires_existing_token() {
+    setup();
+    NftContract::nft_token_uri(U128(1));
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_set_token_uri() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    host::set_caller(alice());
+    NftContract::nft_set_token_uri(U128(1), Some("x".to_string()));
+}
+
+#[test]
+fn metadata_setters_emit_update_events() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    host::clear_captures();
+
+    NftContract::nft_set_base_uri(Some("ipfs://c/".to_string()));
+    NftContract::nft_set_token_uri(U128(1), Some("1.json".to_string()));
+
+    assert_eq!(
+        host::decoded_events::<NftEvent>(),
+        vec![
+            NftEvent::BatchMetadataUpdate {
+                from_token_id: U128(1),
+                to_token_id: U128(1),
+            },
+            NftEvent::MetadataUpdate { token_id: U128(1) },
+        ]
+    );
+}
+
+#[test]
+fn base_uri_update_covers_the_highest_minted_id() {
+    setup_with_max_supply(None);
+    NftContract::nft_mint_id_to(alice(), U128(500));
+    NftContract::nft_mint_to(alice());
+    burn_as_holder(500);
+    host::clear_captures();
+
+    NftContract::nft_set_base_uri(Some("ipfs://c/".to_string()));
+
+    assert_eq!(
+        host::decoded_events::<NftEvent>(),
+        vec![NftEvent::BatchMetadataUpdate {
+            from_token_id: U128(1),
+            to_token_id: U128(500),
+        }]
+    );
+}
+
+#[test]
+fn base_uri_update_without_tokens_emits_nothing() {
+    setup_with_max_supply(None);
+    host::clear_captures();
+
+    NftContract::nft_set_base_uri(Some("ipfs://c/".to_string()));
+
+    assert!(host::decoded_events::<NftEvent>().is_empty());
+}
+
+#[test]
+fn mint_transfer_and_burn_emit_events() {
+    setup();
+    host::clear_captures();
+
+    NftContract::nft_mint_to(alice());
+    host::set_caller(alice());
+    NftContract::nft_transfer_from(alice(), bob(), U128(1));
+    host::set_caller(bob());
+    NftContract::nft_burn(U128(1));
+
+    assert_eq!(
+        host::decoded_events::<NftEvent>(),
+        vec![
+            NftEvent::Transfer {
+                from: zero_address(),
+                to: alice(),
+                token_id: U128(1),
+            },
+            NftEvent::Transfer { from: alice(), to: bob(), token_id: U128(1) },
+            NftEvent::Transfer {
+                from: bob(),
+                to: zero_address(),
+                token_id: U128(1),
+            },
+        ]
+    );
+}
+
+#[test]
+fn approvals_emit_events() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    host::clear_captures();
+
+    host::set_caller(alice());
+    NftContract::nft_approve(bob(), U128(1));
+    NftContract::nft_set_approval_for_all(carol(), true);
+
+    assert_eq!(
+        host::decoded_events::<NftEvent>(),
+        vec![
+            NftEvent::Approval {
+                owner: alice(),
+                approved: bob(),
+                token_id: U128(1)
+            },
+            NftEvent::ApprovalForAll {
+                owner: alice(),
+                operator: carol(),
+                approved: true
+            },
+        ]
+    );
+}
+
+#[test]
+fn pause_emits_events() {
+    setup();
+    host::clear_captures();
+
+    NftContract::nft_pause();
+    NftContract::nft_unpause();
+
+    let owner = host::contract_owner();
+    assert_eq!(
+        host::decoded_events::<NftEvent>(),
+        vec![
+            NftEvent::Paused { account: owner },
+            NftEvent::Unpaused { account: owner }
+        ]
+    );
+}
+
+#[test]
+fn events_encode_as_json() {
+    let event =
+        NftEvent::Transfer { from: alice(), to: bob(), token_id: U128(7) };
+
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["Transfer"]["token_id"], "7");
+    assert_eq!(
+        json["Transfer"]["from"],
+        serde_json::to_value(alice()).unwrap()
+    );
+    assert_eq!(serde_json::from_value::<NftEvent>(json).unwrap(), event);
+}
+
+#[test]
+fn unlimited_supply_has_no_cap() {
+    setup_with_max_supply(None);
+
+    NftContract::nft_mint_id_to(alice(), U128(1_000_000));
+
+    assert_eq!(NftContract::nft_max_supply(), None);
+    assert_eq!(NftContract::nft_remaining_supply(), None);
+    assert_eq!(NftContract::nft_owner_of(U128(1_000_000)), alice());
+}
+
+#[test]
+fn remaining_supply_counts_minted_tokens() {
+    setup_with_max_supply(Some(3));
+    assert_eq!(NftContract::nft_remaining_supply(), Some(U128(3)));
+
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(alice());
+    burn_as_holder(1);
+
+    assert_eq!(NftContract::nft_remaining_supply(), Some(U128(1)));
+}
+
+#[test]
+#[should_panic(expected = "Max supply reached")]
+fn burning_does_not_free_supply() {
+    setup_with_max_supply(Some(2));
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(alice());
+    burn_as_holder(1);
+
+    NftContract::nft_mint_id_to(alice(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "is missing role Minter")]
+fn only_minters_mint_by_default() {
+    setup();
+    assert_eq!(NftContract::nft_mint_policy(), MintPolicy::MinterOnly);
+
+    host::set_caller(alice());
+    NftContract::nft_mint_to(alice());
+}
+
+#[test]
+fn public_mint_enforces_per_address_limit() {
+    setup();
+    NftContract::nft_set_mint_policy(MintPolicy::Public {
+        per_address_limit: Some(2),
+    });
+
+    host::set_caller(alice());
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(bob());
+    assert_eq!(NftContract::nft_minted_by(alice()), 2);
+
+    host::set_caller(bob());
+    NftContract::nft_mint_to(bob());
+    assert_eq!(NftContract::nft_balance_of(bob()).0, 2);
+}
+
+#[test]
+#[should_panic(expected = "The mint limit of 1 tokens per address is reached")]
+fn public_mint_rejects_over_limit() {
+    setup();
+    NftContract::nft_set_mint_policy(MintPolicy::Public {
+        per_address_limit: Some(1),
+    });
+
+    host::set_caller(alice());
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(alice());
+}
+
+#[test]
+fn allowlisted_accounts_can_mint() {
+    setup();
+    NftContract::nft_set_mint_policy(MintPolicy::Allowlist {
+        per_address_limit: None,
+    });
+    NftContract::nft_set_mint_allowlisted(vec![alice()], true);
+    assert!(NftContract::nft_is_mint_allowlisted(alice()));
+
+    host::set_caller(alice());
+    NftContract::nft_mint_to(alice());
+
+    assert_eq!(owned(alice()), vec![1]);
+}
+
+#[test]
+#[should_panic(expected = "is not allowlisted to mint")]
+fn allowlist_mint_rejects_others() {
+    setup();
+    NftContract::nft_set_mint_policy(MintPolicy::Allowlist {
+        per_address_limit: None,
+    });
+    NftContract::nft_set_mint_allowlisted(vec![alice()], true);
+    NftContract::nft_set_mint_allowlisted(vec![alice()], false);
+
+    host::set_caller(alice());
+    NftContract::nft_mint_to(alice());
+}
+
+#[test]
+fn paid_mint_forwards_the_price() {
+    setup();
+    NftContract::nft_set_mint_policy(MintPolicy::Paid {
+        price: U128(30),
+        payee: carol(),
+        per_address_limit: None,
+    });
+    host::set_native_balance(&alice(), 100);
+
+    host::set_caller(alice());
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(bob());
+
+    assert_eq!(host::native_balance(&alice()), 40);
+    assert_eq!(host::native_balance(&carol()), 60);
+    assert_eq!(host::native_balance(&host::contract_instance()), 0);
+    assert_eq!(NftContract::nft_owner_of(U128(2)), bob());
+}
+
+#[test]
+#[should_panic(expected = "The caller can't pay 30 native tokens")]
+fn paid_mint_rejects_an_underfunded_caller() {
+    setup();
+    NftContract::nft_set_mint_policy(MintPolicy::Paid {
+        price: U128(30),
+        payee: carol(),
+        per_address_limit: None,
+    });
+    host::set_native_balance(&alice(), 20);
+
+    host::set_caller(alice());
+    NftContract::nft_mint_to(alice());
+}
+
+#[test]
+#[should_panic(expected = "The mint price must be positive")]
+fn paid_mint_requires_a_price() {
+    setup();
+    NftContract::nft_set_mint_policy(MintPolicy::Paid {
+        price: U128(0),
+        payee: carol(),
+        per_address_limit: None,
+    });
+}
+
+#[test]
+fn mint_window_bounds_policy_mints() {
+    setup();
+    NftContract::nft_set_mint_policy(MintPolicy::Public {
+        per_address_limit: None,
+    });
+    NftContract::nft_set_mint_window(Some(U64(100)), Some(U64(200)));
+    let (start, end) = NftContract::nft_mint_window();
+    assert_eq!((start.map(|t| t.0), end.map(|t| t.0)), (Some(100), Some(200)));
+
+    host::set_block(1, 150);
+    host::set_caller(alice());
+    NftContract::nft_mint_to(alice());
+
+    // Minters aren't bound by the window
+    host::set_block(2, 250);
+    host::set_caller(host::contract_owner());
+    NftContract::nft_mint_to(bob());
+
+    assert_eq!(NftContract::nft_minted_total().0, 2);
+}
+
+#[test]
+#[should_panic(expected = "The mint hasn't started yet")]
+fn mint_window_rejects_early_mints() {
+    setup();
+    NftContract::nft_set_mint_policy(MintPolicy::Public {
+        per_address_limit: None,
+    });
+    NftContract::nft_set_mint_window(Some(U64(100)), None);
+
+    host::set_block(1, 99);
+    host::set_caller(alice());
+    NftContract::nft_mint_to(alice());
+}
+
+#[test]
+#[should_panic(expected = "The mint is over")]
+fn mint_window_rejects_late_mints() {
+    setup();
+    NftContract::nft_set_mint_policy(MintPolicy::Public {
+        per_address_limit: None,
+    });
+    NftContract::nft_set_mint_window(None, Some(U64(200)));
+
+    host::set_block(1, 200);
+    host::set_caller(alice());
+    NftContract::nft_mint_to(alice());
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_set_mint_policy() {
+    setup();
+
+    host::set_caller(alice());
+    NftContract::nft_set_mint_policy(MintPolicy::Public {
+        per_address_limit: None,
+    });
+}
+
+fn receiver() -> Address {
+    host::address(0x5e)
+}
+
+#[test]
+fn safe_transfer_notifies_the_receiver() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    host::mock_call(
+        &receiver(),
+        "nft_on_received",
+        Some(br#""nft_on_received""#.to_vec()),
+    );
+
+    host::set_caller(alice());
+    NftContract::nft_approve(bob(), U128(1));
+    host::set_caller(bob());
+    NftContract::nft_safe_transfer_from(
+        alice(),
+        receiver(),
+        U128(1),
+        "hello".to_string(),
+    );
+
+    assert_eq!(NftContract::nft_owner_of(U128(1)), receiver());
+    let calls = host::calls();
+    assert_eq!(calls.len(), 1);
+    let args: serde_json::Value =
+        serde_json::from_slice(&calls[0].args).unwrap();
+    assert_eq!(args["operator"], serde_json::to_value(bob()).unwrap());
+    assert_eq!(args["from"], serde_json::to_value(alice()).unwrap());
+    assert_eq!(args["id"], "1");
+    assert_eq!(args["data"], "hello");
+}
+
+#[test]
+#[should_panic(expected = "rejected the token_id 1")]
+fn safe_transfer_reverts_without_the_ack() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    host::mock_call(&receiver(), "nft_on_received", Some(br#""""#.to_vec()));
+
+    host::set_caller(alice());
+    NftContract::nft_safe_transfer_from(
+        alice(),
+        receiver(),
+        U128(1),
+        String::new(),
+    );
+}
+
+#[test]
+#[should_panic(expected = "rejected the token_id 1")]
+fn safe_transfer_reverts_on_an_unreadable_answer() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    host::mock_call(&receiver(), "nft_on_received", Some(b"{}".to_vec()));
+
+    host::set_caller(alice());
+    NftContract::nft_safe_transfer_from(
+        alice(),
+        receiver(),
+        U128(1),
+        String::new(),
+    );
+}
+
+#[test]
+fn safe_transfer_to_an_account_reverts() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    // Nothing answers for an account without contract code
+    host::set_caller(alice());
+    let result = host::transact(|| {
+        NftContract::nft_safe_transfer_from(
+            alice(),
+            bob(),
+            U128(1),
+            String::new(),
+        )
+    });
+
+    assert!(result.unwrap_err().contains("rejected the token_id 1"));
+    assert_eq!(NftContract::nft_owner_of(U128(1)), alice());
+    assert_eq!(owned(bob()), Vec::<u128>::new());
+}
+
+#[test]
+fn safe_transfer_reverts_when_the_receiver_fails() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    // The receiver panics, so the call has no result
+    host::mock_call(&receiver(), "nft_on_received", None);
+
+    host::set_caller(alice());
+    let result = host::transact(|| {
+        NftContract::nft_safe_transfer_from(
+            alice(),
+            receiver(),
+            U128(1),
+            String::new(),
+        )
+    });
+
+    assert!(result.unwrap_err().contains("rejected the token_id 1"));
+    assert_eq!(NftContract::nft_owner_of(U128(1)), alice());
+    assert_eq!(NftContract::nft_balance_of(receiver()).0, 0);
+}
+
+#[test]
+fn total_supply_follows_mints_and_burns() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(bob());
+    NftContract::nft_mint_to(alice());
+    assert_eq!(NftContract::nft_total_supply().0, 3);
+
+    burn_as_holder(1);
+
+    assert_eq!(NftContract::nft_total_supply().0, 2);
+    let mut ids: Vec<u128> =
+        (0..2).map(|idx| NftContract::nft_token_by_index(idx).0).collect();
+    ids.sort();
+    assert_eq!(ids, vec![2, 3]);
+}
+
+#[test]
+fn transfers_keep_the_token_index() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(alice());
+
+    host::set_caller(alice());
+    NftContract::nft_transfer_from(alice(), bob(), U128(1));
+
+    assert_eq!(NftContract::nft_total_supply().0, 2);
+    assert_eq!(NftContract::nft_token_by_index(0).0, 1);
+    assert_eq!(NftContract::nft_token_by_index(1).0, 2);
+}
+
+#[test]
+#[should_panic(expected = "Index 1 is out of bounds of the token index")]
+fn token_by_index_rejects_out_of_bounds() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    burn_as_holder(1);
+    NftContract::nft_mint_to(alice());
+
+    NftContract::nft_token_by_index(1);
+}
+
+#[test]
+fn tokens_of_owner_pages() {
+    setup();
+    for _ in 0..5 {
+        NftContract::nft_mint_to(alice());
+    }
+
+    let page = |from_index, limit| -> Vec<u128> {
+        NftContract::nft_tokens_of_owner(alice(), from_index, limit)
+            .into_iter()
+            .map(|id| id.0)
+            .collect()
+    };
+    assert_eq!(page(0, 2), vec![1, 2]);
+    assert_eq!(page(2, 2), vec![3, 4]);
+    assert_eq!(page(4, 2), vec![5]);
+    assert!(page(5, 2).is_empty());
+}
+
+#[test]
+fn owners_without_tokens_have_none() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    host::set_caller(alice());
+    NftContract::nft_transfer_from(alice(), bob(), U128(1));
+
+    assert!(NftContract::nft_owned_tokens(alice()).is_empty());
+    assert!(NftContract::nft_owned_tokens(carol()).is_empty());
+    assert!(NftContract::nft_tokens_of_owner(carol(), 0, 10).is_empty());
+}
+
+fn royalty(receiver: Address, bps: u16) -> Royalty {
+    Royalty { receiver, bps, splits: Vec::new() }
+}
+
+fn setup_with_royalty() {
+    host::set_caller(host::contract_owner());
+    NftContract::new(metadata(), None, Some(royalty(carol(), 500)));
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(alice());
+}
+
+#[test]
+fn royalty_info_uses_the_default_royalty() {
+    setup_with_royalty();
+
+    let (receiver, amount) =
+        NftContract::nft_royalty_info(U128(1), U128(1_000));
+
+    assert_eq!(receiver, carol());
+    assert_eq!(amount.0, 50);
+    assert_eq!(NftContract::nft_default_royalty(), Some(royalty(carol(), 500)));
+}
+
+#[test]
+fn royalty_info_is_empty_without_royalty() {
+    setup();
+    NftContract::nft_mint_to(alice());
+
+    let (receiver, amount) =
+        NftContract::nft_royalty_info(U128(1), U128(1_000));
+
+    assert_eq!(receiver, zero_address());
+    assert_eq!(amount.0, 0);
+    assert!(NftContract::nft_royalty_splits(U128(1), U128(1_000)).is_empty());
+}
+
+#[test]
+fn token_royalty_overrides_the_default() {
+    setup_with_royalty();
+    NftContract::nft_set_token_royalty(U128(2), Some(royalty(bob(), 1_000)));
+
+    let (receiver, amount) =
+        NftContract::nft_royalty_info(U128(1), U128(1_000));
+    assert_eq!((receiver, amount.0), (carol(), 50));
+    let (receiver, amount) =
+        NftContract::nft_royalty_info(U128(2), U128(1_000));
+    assert_eq!((receiver, amount.0), (bob(), 100));
+
+    NftContract::nft_set_token_royalty(U128(2), None);
+    let (receiver, _) = NftContract::nft_royalty_info(U128(2), U128(1_000));
+    assert_eq!(receiver, carol());
+}
+
+#[test]
+fn royalty_splits_share_the_royalty() {
+    setup_with_royalty();
+    NftContract::nft_set_default_royalty(Some(Royalty {
+        receiver: carol(),
+        bps: 1_000,
+        splits: vec![
+            RoyaltySplit { receiver: carol(), bps: 3_333 },
+            RoyaltySplit { receiver: bob(), bps: 6_667 },
+        ],
+    }));
+
+    let splits: Vec<(Address, u128)> =
+        NftContract::nft_royalty_splits(U128(1), U128(1_000))
+            .into_iter()
+            .map(|(receiver, amount)| (receiver, amount.0))
+            .collect();
+
+    // 33.33 and 66.67 of the 100 royalty, the dust goes to the first one
+    assert_eq!(splits, vec![(carol(), 34), (bob(), 66)]);
+    let (receiver, amount) =
+        NftContract::nft_royalty_info(U128(1), U128(1_000));
+    assert_eq!((receiver, amount.0), (carol(), 100));
+}
+
+#[test]
+#[should_panic(expected = "Royalty splits must add up to 10000 bps")]
+fn royalty_splits_must_cover_the_royalty() {
+    setup_with_royalty();
+    NftContract::nft_set_default_royalty(Some(Royalty {
+        receiver: carol(),
+        bps: 1_000,
+        splits: vec![RoyaltySplit { receiver: bob(), bps: 5_000 }],
+    }));
+}
+
+#[test]
+#[should_panic(expected = "Royalty bps can't exceed 10000")]
+fn royalty_bps_is_capped() {
+    host::set_caller(host::contract_owner());
+    NftContract::new(metadata(), None, Some(royalty(carol(), 10_001)));
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_set_royalty() {
+    setup_with_royalty();
+
+    host::set_caller(alice());
+    NftContract::nft_set_token_royalty(U128(1), Some(royalty(alice(), 10_000)));
+}
+
+#[test]
+fn burn_clears_token_royalty() {
+    setup_with_royalty();
+    NftContract::nft_set_token_royalty(U128(1), Some(royalty(bob(), 1_000)));
+    burn_as_holder(1);
+    NftContract::nft_mint_id_to(alice(), U128(1));
+
+    let (receiver, _) = NftContract::nft_royalty_info(U128(1), U128(1_000));
+    assert_eq!(receiver, carol());
+}
+
+#[test]
+fn batch_mint_assigns_next_free_ids() {
+    setup();
+    NftContract::nft_mint_id_to(bob(), U128(2));
+    host::clear_captures();
+
+    let ids = NftContract::nft_batch_mint(alice(), 3);
+
+    assert_eq!(ids.iter().map(|id| id.0).collect::<Vec<_>>(), vec![1, 3, 4]);
+    assert_eq!(owned(alice()), vec![1, 3, 4]);
+    assert_eq!(NftContract::nft_minted_total().0, 4);
+    assert_eq!(host::decoded_events::<NftEvent>().len(), 3);
+}
+
+#[test]
+#[should_panic(expected = "The mint limit of 2 tokens per address is reached")]
+fn batch_mint_counts_against_the_mint_limit() {
+    setup();
+    NftContract::nft_set_mint_policy(MintPolicy::Public {
+        per_address_limit: Some(2),
+    });
+
+    host::set_caller(alice());
+    NftContract::nft_batch_mint(alice(), 3);
+}
+
+#[test]
+#[should_panic(expected = "Max supply reached")]
+fn batch_mint_enforces_cap() {
+    setup_with_max_supply(Some(2));
+
+    NftContract::nft_batch_mint(alice(), 3);
+}
+
+#[test]
+fn batch_mint_ids_mints_every_id() {
+    setup();
+
+    NftContract::nft_batch_mint_ids(alice(), vec![U128(5), U128(7)]);
+
+    assert_eq!(owned(alice()), vec![5, 7]);
+    assert_eq!(NftContract::nft_total_supply().0, 2);
+}
+
+#[test]
+#[should_panic(expected = "Token ID 5 is already minted")]
+fn batch_mint_ids_rejects_duplicates() {
+    setup();
+
+    NftContract::nft_batch_mint_ids(alice(), vec![U128(5), U128(5)]);
+}
+
+#[test]
+fn batch_transfer_moves_every_token() {
+    setup();
+    NftContract::nft_batch_mint(alice(), 3);
+    host::clear_captures();
+
+    host::set_caller(alice());
+    NftContract::nft_set_approval_for_all(bob(), true);
+    host::set_caller(bob());
+    NftContract::nft_batch_transfer_from(
+        alice(),
+        carol(),
+        vec![U128(1), U128(3)],
+    );
+
+    assert_eq!(owned(alice()), vec![2]);
+    assert_eq!(owned(carol()), vec![1, 3]);
+    assert_eq!(host::decoded_events::<NftEvent>().len(), 3);
+}
+
+#[test]
+#[should_panic(expected = "Not Authorized")]
+fn batch_transfer_checks_every_token() {
+    setup();
+    NftContract::nft_mint_to(alice());
+    NftContract::nft_mint_to(bob());
+
+    host::set_caller(alice());
+    NftContract::nft_batch_transfer_from(
+        alice(),
+        carol(),
+        vec![U128(1), U128(2)],
+    );
+}
+
+#[test]
+fn batch_burn_burns_every_token() {
+    setup();
+    NftContract::nft_batch_mint(alice(), 3);
+    host::clear_captures();
+
+    host::set_caller(alice());
+    NftContract::nft_batch_burn(vec![U128(1), U128(2)]);
+
+    assert_eq!(
+        host::decoded_events::<NftEvent>(),
+        vec![
+            NftEvent::Transfer {
+                from: alice(),
+                to: zero_address(),
+                token_id: U128(1),
+            },
+            NftEvent::Transfer {
+                from: alice(),
+                to: zero_address(),
+                token_id: U128(2),
+            },
+        ]
+    );
+    assert_eq!(owned(alice()), vec![3]);
+    assert_eq!(NftContract::nft_total_supply().0, 1);
+}
+
+#[test]
+#[should_panic(expected = "The batch is empty")]
+fn batch_burn_rejects_empty_batches() {
+    setup();
+
+    NftContract::nft_batch_burn(Vec::new());
+}
+
+fn voucher_signer() -> (LocalWallet, Address) {
+    let wallet = LocalWallet::from_bytes(&[0x22; 32]).unwrap();
+    let address = Address::from(wallet.address().0);
+    (wallet, address)
+}
+
+fn voucher() -> MintVoucher {
+    MintVoucher {
+        token_id: U128(42),
+        uri: "ipfs://42".to_string(),
+        min_price: U128(25),
+        expiry: U64(1_000),
+        recipient: bob(),
+    }
+}
+
+fn sign_voucher(wallet: &LocalWallet, voucher: &MintVoucher) -> String {
+    let digest = NftContract::nft_voucher_digest(voucher.clone());
+    let digest = H256::from_slice(&hex::decode(digest).unwrap());
+    wallet.sign_hash(digest).unwrap().to_string()
+}
+
+/// Enables vouchers and gives carol native tokens to redeem them.
+fn setup_vouchers() -> (LocalWallet, Address) {
+    let (wallet, signer) = voucher_signer();
+    setup();
+    NftContract::nft_set_voucher_signer(Some(signer));
+    host::set_native_balance(&carol(), 100);
+    host::set_block(1, 500);
+    (wallet, signer)
+}
+
+#[test]
+fn redeem_voucher_mints_and_pays_the_signer() {
+    let (wallet, signer) = setup_vouchers();
+    let signature = sign_voucher(&wallet, &voucher());
+
+    host::set_caller(carol());
+    let id = NftContract::nft_redeem_voucher(voucher(), signature);
+
+    assert_eq!(id.0, 42);
+    assert_eq!(NftContract::nft_owner_of(U128(42)), bob());
+    assert_eq!(NftContract::nft_token_uri(U128(42)), "ipfs://42");
+    assert_eq!(host::native_balance(&carol()), 75);
+    assert_eq!(host::native_balance(&signer), 25);
+    assert!(NftContract::nft_is_voucher_redeemed(voucher()));
+}
+
+#[test]
+#[should_panic(expected = "The caller can't pay 25 native tokens")]
+fn voucher_redemption_rejects_an_underfunded_caller() {
+    let (wallet, _) = setup_vouchers();
+    let signature = sign_voucher(&wallet, &voucher());
+    host::set_native_balance(&carol(), 10);
+
+    host::set_caller(carol());
+    NftContract::nft_redeem_voucher(voucher(), signature);
+}
+
+#[test]
+#[should_panic(expected = "The voucher is already redeemed")]
+fn voucher_cannot_be_redeemed_twice() {
+    let (wallet, _) = setup_vouchers();
+    let signature = sign_voucher(&wallet, &voucher());
+
+    host::set_caller(carol());
+    NftContract::nft_redeem_voucher(voucher(), signature.clone());
+    burn_as_holder(42);
+    host::set_caller(carol());
+    NftContract::nft_redeem_voucher(voucher(), signature);
+}
+
+#[test]
+#[should_panic(expected = "The voucher has expired")]
+fn expired_voucher_is_rejected() {
+    let (wallet, _) = setup_vouchers();
+    let signature = sign_voucher(&wallet, &voucher());
+    host::set_block(2, 1_001);
+
+    host::set_caller(carol());
+    NftContract::nft_redeem_voucher(voucher(), signature);
+}
+
+#[test]
+#[should_panic(expected = "Invalid voucher signature")]
+fn voucher_signed_by_another_key_is_rejected() {
+    setup_vouchers();
+    let wallet = LocalWallet::from_bytes(&[0x33; 32]).unwrap();
+    let signature = sign_voucher(&wallet, &voucher());
+
+    host::set_caller(carol());
+    NftContract::nft_redeem_voucher(voucher(), signature);
+}
+
+#[test]
+#[should_panic(expected = "Invalid voucher signature")]
+fn tampered_voucher_is_rejected() {
+    let (wallet, _) = setup_vouchers();
+    let signature = sign_voucher(&wallet, &voucher());
+
+    let mut voucher = voucher();
+    voucher.min_price = U128(0);
+    host::set_caller(carol());
+    NftContract::nft_redeem_voucher(voucher, signature);
+}
+
+#[test]
+#[should_panic(expected = "Vouchers aren't enabled")]
+fn vouchers_require_a_signer() {
+    let (wallet, _) = voucher_signer();
+    setup();
+    let signature = sign_voucher(&wallet, &voucher());
+
+    NftContract::nft_redeem_voucher(voucher(), signature);
+}
+
+#[test]
+#[should_panic(expected = "is missing role Admin")]
+fn non_admin_cannot_set_voucher_signer() {
+    setup();
+
+    host::set_caller(alice());
+    NftContract::nft_set_voucher_signer(Some(alice()));
+}
@@ -0,0 +1,26 @@
+use l1x_mock_host as host;
+use l1x_sdk::types::U128;
+use l1x_transfer_token::Contract;
+
+#[test]
+fn fund_contract_moves_caller_funds() {
+    let alice = host::address(1);
+    host::set_caller(alice);
+    host::set_native_balance(&alice, 1000);
+
+    Contract::fund_contract(U128(400));
+
+    assert_eq!(Contract::caller_balance().0, 600);
+    assert_eq!(Contract::contract_balance().0, 400);
+}
+
+#[test]
+fn transfer_pays_out_from_the_contract() {
+    let bob = host::address(2);
+    host::set_native_balance(&host::contract_instance(), 1000);
+
+    Contract::transfer(bob, U128(250));
+
+    assert_eq!(host::native_balance(&bob), 250);
+    assert_eq!(Contract::contract_balance().0, 750);
+}
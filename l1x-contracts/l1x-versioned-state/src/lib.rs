@@ -0,0 +1,47 @@
+//! Versioned storage envelope shared by `l1x-ft` and `l1x-nft`.
+//!
+//! A contract stores its state as `STATE_MAGIC || version (u16 LE) ||
+//! payload`, where the payload is the Borsh encoding of the layout of that
+//! version. State written before versioning is a bare payload and reads as
+//! version 0: it's recognized by the missing magic. Such payloads start with
+//! a string, whose Borsh encoding starts with its length as a u32 LE. A
+//! length of 255 mod 256 starts with `0xff` like the magic, but matching all
+//! four bytes takes a length of exactly `0x726576ff`, a string of about 1.9GB
+//! that no contract stores.
+//!
+//! The contract keeps its current version and the steps that convert older
+//! payloads, see the `state` module of `l1x-ft` and `l1x-nft`.
+
+const STATE_MAGIC: &[u8; 4] = b"\xffver";
+
+/// Returns the stored version and payload, or `None` if nothing is stored.
+pub fn read(key: &[u8]) -> Option<(u16, Vec<u8>)> {
+    let bytes = l1x_sdk::storage_read(key)?;
+
+    match bytes.strip_prefix(STATE_MAGIC.as_slice()) {
+        Some(versioned) => {
+            assert!(versioned.len() >= 2, "The stored state is truncated");
+            let version = u16::from_le_bytes([versioned[0], versioned[1]]);
+            Some((version, versioned[2..].to_vec()))
+        }
+        None => Some((0, bytes)),
+    }
+}
+
+/// Stores `payload` as the state of layout `version`.
+pub fn write(key: &[u8], version: u16, payload: &[u8]) {
+    let bytes =
+        [STATE_MAGIC.as_slice(), &version.to_le_bytes(), payload].concat();
+    l1x_sdk::storage_write(key, &bytes);
+}
+
+/// Panics if the stored `version` is newer than the `supported` one, which
+/// happens when an instance is downgraded to older code.
+pub fn assert_not_newer(version: u16, supported: u16) {
+    assert!(
+        version <= supported,
+        "The stored state version {} is newer than the supported version {}",
+        version,
+        supported
+    );
+}
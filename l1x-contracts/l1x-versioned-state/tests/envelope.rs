@@ -0,0 +1,43 @@
+use l1x_mock_host as host;
+
+const KEY: &[u8] = b"state";
+
+#[test]
+fn write_and_read_round_trip() {
+    l1x_versioned_state::write(KEY, 7, b"payload");
+
+    assert_eq!(l1x_versioned_state::read(KEY), Some((7, b"payload".to_vec())));
+    assert_eq!(&host::storage_get(KEY).unwrap()[4..6], &7u16.to_le_bytes());
+}
+
+#[test]
+fn missing_state_reads_as_none() {
+    assert_eq!(l1x_versioned_state::read(KEY), None);
+}
+
+#[test]
+fn unversioned_state_reads_as_version_0() {
+    l1x_sdk::storage_write(KEY, b"\x06\x00\x00\x00Legacy");
+
+    assert_eq!(
+        l1x_versioned_state::read(KEY),
+        Some((0, b"\x06\x00\x00\x00Legacy".to_vec()))
+    );
+}
+
+#[test]
+#[should_panic(expected = "The stored state is truncated")]
+fn truncated_header_is_rejected() {
+    l1x_sdk::storage_write(KEY, b"\xffver\x01");
+
+    l1x_versioned_state::read(KEY);
+}
+
+#[test]
+#[should_panic(
+    expected = "The stored state version 3 is newer than the supported version 2"
+)]
+fn newer_version_is_rejected() {
+    l1x_versioned_state::assert_not_newer(2, 2);
+    l1x_versioned_state::assert_not_newer(3, 2);
+}
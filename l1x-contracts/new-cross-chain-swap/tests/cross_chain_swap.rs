@@ -0,0 +1,139 @@
+use base64::Engine;
+use ethers::abi::{encode, Token};
+use ethers::types::{Address, Log, H256, U256};
+use ethers::utils::keccak256;
+use l1x_sdk::types::U64;
+use new_cross_chain_swap::CrossChainSwapFlow;
+
+const GLOBAL_TX_ID: &str = "tx-1";
+const RAW_GLOBAL_TX_ID: [u8; 32] = [0x42; 32];
+
+fn encode_log(log: &Log) -> String {
+    base64::engine::general_purpose::STANDARD
+        .encode(serde_json::to_vec(log).unwrap())
+}
+
+fn swap_initiated_log(destination_network: &str) -> Log {
+    let signature = keccak256(
+        "SwapInitiated(bytes32,uint256,uint256,uint256,address,address,address,address,string,string,string)",
+    );
+    Log {
+        topics: vec![
+            H256(signature),
+            H256(RAW_GLOBAL_TX_ID),
+            H256::from(Address::repeat_byte(2)),
+            H256::from(Address::repeat_byte(4)),
+        ],
+        data: encode(&[
+            Token::Uint(U256::from(1)),
+            Token::Uint(U256::from(1000)),
+            Token::Uint(U256::from(990)),
+            Token::Address(Address::repeat_byte(1)),
+            Token::Address(Address::repeat_byte(3)),
+            Token::String("USDC".to_string()),
+            Token::String("USDT".to_string()),
+            Token::String(destination_network.to_string()),
+        ])
+        .into(),
+        ..Default::default()
+    }
+}
+
+fn swap_fullfilled_log() -> Log {
+    let signature = keccak256(
+        "SwapFullfilled(bytes32,uint256,address,address,string,string)",
+    );
+    Log {
+        topics: vec![
+            H256(signature),
+            H256(RAW_GLOBAL_TX_ID),
+            H256::from(Address::repeat_byte(2)),
+            H256::from(Address::repeat_byte(4)),
+        ],
+        data: encode(&[
+            Token::Uint(U256::from(990)),
+            Token::String("USDC".to_string()),
+            Token::String("BSC".to_string()),
+        ])
+        .into(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn swap_initiated_produces_an_execute_payload() {
+    CrossChainSwapFlow::new();
+
+    CrossChainSwapFlow::save_event_data(
+        GLOBAL_TX_ID.to_string(),
+        U64(0),
+        encode_log(&swap_initiated_log("BSC")),
+    );
+
+    assert_eq!(CrossChainSwapFlow::total_events().0, 1);
+    assert_eq!(
+        CrossChainSwapFlow::get_payload_hash_to_sign(GLOBAL_TX_ID.to_string()),
+        hex::encode(keccak256(RAW_GLOBAL_TX_ID))
+    );
+}
+
+#[test]
+fn get_pay_load_targets_the_destination_network() {
+    CrossChainSwapFlow::new();
+    CrossChainSwapFlow::save_event_data(
+        GLOBAL_TX_ID.to_string(),
+        U64(0),
+        encode_log(&swap_initiated_log("BSC")),
+    );
+    let signature = format!("0x{}1b", "11".repeat(64));
+
+    let response =
+        CrossChainSwapFlow::get_pay_load(GLOBAL_TX_ID.to_string(), signature);
+
+    let response = serde_json::to_value(response).unwrap();
+    assert_eq!(response["chain_id"], 56);
+    assert!(response["input_data"].as_str().unwrap().len() > 8);
+}
+
+#[test]
+#[should_panic(expected = "invalid global transaction id")]
+fn swap_executed_supersedes_the_execute_payload() {
+    CrossChainSwapFlow::new();
+    CrossChainSwapFlow::save_event_data(
+        GLOBAL_TX_ID.to_string(),
+        U64(0),
+        encode_log(&swap_initiated_log("ETH")),
+    );
+    CrossChainSwapFlow::save_event_data(
+        GLOBAL_TX_ID.to_string(),
+        U64(1),
+        encode_log(&swap_fullfilled_log()),
+    );
+    assert_eq!(CrossChainSwapFlow::total_events().0, 2);
+
+    CrossChainSwapFlow::get_payload_hash_to_sign(GLOBAL_TX_ID.to_string());
+}
+
+#[test]
+#[should_panic(expected = "Unknown source id: 5")]
+fn save_event_data_rejects_unknown_sources() {
+    CrossChainSwapFlow::new();
+
+    CrossChainSwapFlow::save_event_data(
+        GLOBAL_TX_ID.to_string(),
+        U64(5),
+        encode_log(&swap_fullfilled_log()),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Can't decode base64 event_data")]
+fn save_event_data_rejects_invalid_base64() {
+    CrossChainSwapFlow::new();
+
+    CrossChainSwapFlow::save_event_data(
+        GLOBAL_TX_ID.to_string(),
+        U64(0),
+        "not base64!".to_string(),
+    );
+}
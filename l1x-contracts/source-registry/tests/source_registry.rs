@@ -0,0 +1,64 @@
+use l1x_mock_host as host;
+use l1x_sdk::types::U64;
+use source_registry::{Operation, SourceRegistry};
+
+fn register(source_id: &str) -> U64 {
+    SourceRegistry::register_new_source(
+        host::address(1).to_string(),
+        source_id.to_string(),
+        "ethereum".to_string(),
+        "evm".to_string(),
+        "0x351a25893e8E729045BE22eF0049E351527168D3".to_string(),
+        "SwapInitiated".to_string(),
+        vec![],
+    )
+    .unwrap()
+}
+
+#[test]
+fn register_new_source_assigns_increasing_indexes() {
+    SourceRegistry::new();
+
+    assert_eq!(register("0").0, 0);
+    assert_eq!(register("1").0, 1);
+
+    let source = SourceRegistry::get_source(U64(1)).unwrap();
+    assert_eq!(source.event_source.source_id, "1");
+    assert_eq!(source.op, Operation::Create);
+}
+
+#[test]
+fn unregister_source_appends_a_remove_op() {
+    SourceRegistry::new();
+    register("0");
+
+    SourceRegistry::unregister_source(U64(0));
+
+    let removal = SourceRegistry::get_source(U64(1)).unwrap();
+    assert_eq!(removal.event_source.source_id, "0");
+    assert_eq!(removal.op, Operation::Remove);
+}
+
+#[test]
+fn unregister_unknown_source_is_a_noop() {
+    SourceRegistry::new();
+
+    SourceRegistry::unregister_source(U64(7));
+
+    assert!(SourceRegistry::get_source(U64(0)).is_none());
+}
+
+#[test]
+fn get_sources_from_returns_the_tail() {
+    SourceRegistry::new();
+    register("0");
+    register("1");
+    register("2");
+
+    let (next, sources) = SourceRegistry::get_sources_from(U64(1));
+
+    assert_eq!(next, 3);
+    let ids: Vec<_> =
+        sources.iter().map(|op| op.event_source.source_id.as_str()).collect();
+    assert_eq!(ids, vec!["1", "2"]);
+}
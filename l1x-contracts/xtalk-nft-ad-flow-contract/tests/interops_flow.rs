@@ -0,0 +1,55 @@
+use l1x_mock_host as host;
+use xtalk_nft_ad_flow_contract::InterOpsFlow;
+
+const GLOBAL_TX_ID: &str = "0xabc";
+
+fn advertisement_started() -> Vec<u8> {
+    serde_json::to_vec(&serde_json::json!({
+        "nft_contract": host::address(5).to_string(),
+        "token_id": 7,
+        "token_uri": "ipfs://token/7",
+        "owner": host::address(1).to_string(),
+        "price": 100,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn save_event_data_counts_events() {
+    InterOpsFlow::save_event_data(
+        GLOBAL_TX_ID.to_string(),
+        "AdvertisementStarted".to_string(),
+        advertisement_started(),
+    );
+    InterOpsFlow::save_event_data(
+        GLOBAL_TX_ID.to_string(),
+        "AdvertisementTransferred".to_string(),
+        advertisement_started(),
+    );
+
+    assert_eq!(InterOpsFlow::total_events(), 2);
+}
+
+#[test]
+fn get_payload_to_sign_is_empty_without_state() {
+    assert!(
+        InterOpsFlow::get_payload_to_sign(GLOBAL_TX_ID.to_string()).is_empty()
+    );
+}
+
+#[test]
+fn update_state_does_not_persist_the_mint_payload() {
+    InterOpsFlow::save_event_data(
+        GLOBAL_TX_ID.to_string(),
+        "AdvertisementStarted".to_string(),
+        advertisement_started(),
+    );
+
+    InterOpsFlow::update_state(GLOBAL_TX_ID.to_string());
+
+    // `update_state` builds the mint payload but never saves the contract,
+    // so nothing is available for signing afterwards.
+    assert!(
+        InterOpsFlow::get_payload_to_sign(GLOBAL_TX_ID.to_string()).is_empty()
+    );
+}